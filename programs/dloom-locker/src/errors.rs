@@ -23,4 +23,64 @@ pub enum LockerError {
     InsufficientFunds,
     #[msg("Batch burn account validation failed")]
     InvalidBatchAccounts,
+    #[msg("The vesting schedule is invalid.")]
+    InvalidSchedule,
+    #[msg("Requested amount exceeds the vested, unclaimed amount.")]
+    ExceedsVestedAmount,
+    #[msg("This lock is not revocable.")]
+    NotRevocable,
+    #[msg("There are no unvested tokens left to revoke.")]
+    NothingToRevoke,
+    #[msg("Revocable locks cannot be topped up.")]
+    RevocableLock,
+    #[msg("The new unlock date must be later than the current one.")]
+    UnlockDateNotLater,
+    #[msg("The unlock date of this schedule cannot be extended.")]
+    ScheduleNotExtendable,
+    #[msg("There is no pending ownership transfer for this wallet.")]
+    NotPendingOwner,
+    #[msg("The unlock date cannot be earlier than the source lock's.")]
+    UnlockDateTooEarly,
+    #[msg("These locks cannot be merged.")]
+    IncompatibleLocks,
+    #[msg("The signer is not allowed to manage this lock.")]
+    Unauthorized,
+    #[msg("The receipt accounts for this lock are missing or do not match.")]
+    ReceiptAccountsMissing,
+    #[msg("Ownership of a tokenized lock moves with its receipt token.")]
+    LockIsTokenized,
+    #[msg("The config values are invalid.")]
+    InvalidConfig,
+    #[msg("Lock duration is shorter than the configured minimum.")]
+    LockDurationTooShort,
+    #[msg("This feature is currently disabled.")]
+    FeatureDisabled,
+    #[msg("There is no pending admin handover for this wallet.")]
+    NotPendingAdmin,
+    #[msg("The treasury token account is missing or does not match.")]
+    TreasuryAccountMissing,
+    #[msg("The referrer accounts are missing, do not match, or refer the funder.")]
+    InvalidReferrer,
+    #[msg("The program is paused.")]
+    ProgramPaused,
+    #[msg("Withdrawals are paused.")]
+    WithdrawalsPaused,
+    #[msg("This mint is not allowed to be locked.")]
+    MintNotAllowed,
+    #[msg("The mint has extensions or authorities that make a lock unsafe.")]
+    RiskyMint,
+    #[msg("The vault holds no tokens beyond the locked amount.")]
+    NothingToSync,
+    #[msg("A token account to receive the tokens is required.")]
+    TokenAccountMissing,
+    #[msg("Only cliff locks can take more tokens; a vesting schedule would release them retroactively.")]
+    NotCliffLock,
+    #[msg("This lock record is already in the current layout.")]
+    LockAlreadyMigrated,
+    #[msg("Only the beneficiary can pick a lock id; locks funded for another wallet take the next id.")]
+    LockIdNotAllowed,
+    #[msg("A lock cannot be revocable by its own beneficiary.")]
+    SelfRevocableLock,
+    #[msg("Revocable locks cannot be tokenized; the grantor could claw back a sold position.")]
+    RevocableReceipt,
 }
@@ -1,19 +1,38 @@
 // FILE: programs/dloom_locker/src/events.rs
 use anchor_lang::prelude::*;
+use crate::state::{ConfigParams, LockSchedule};
 
 #[event]
 pub struct TokensLocked {
     pub owner: Pubkey,
+    pub lock_id: u64,
+    pub funder: Pubkey,
     pub mint: Pubkey,
     pub amount: u64,
     pub unlock_timestamp: i64,
+    pub schedule: LockSchedule,
+    pub revocable: bool,
+    pub receipt_mint: Pubkey,
+    // Protocol fees charged on creation.
+    pub fee_lamports: u64,
+    pub fee_amount: u64,
+    // `Pubkey::default()` when the lock had no referrer.
+    pub referrer: Pubkey,
+    pub referral_lamports: u64,
+    // Part of `fee_amount` paid to the referrer instead of the treasury.
+    pub referral_amount: u64,
+    pub risk_flags: u8,
 }
 
 #[event]
 pub struct TokensWithdrawn {
     pub owner: Pubkey,
     pub mint: Pubkey,
+    // Gross amount taken out of the vault.
     pub amount: u64,
+    // Token-2022 transfer fee withheld from it, and what the owner actually received.
+    pub fee: u64,
+    pub net_amount: u64,
 }
 
 #[event]
@@ -36,4 +55,149 @@ pub struct BatchTokensBurned {
     pub burner: Pubkey,
     pub mints: Vec<Pubkey>,
     pub amounts: Vec<u64>,
-}
\ No newline at end of file
+}
+
+#[event]
+pub struct LockRevoked {
+    pub funder: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub lock_id: u64,
+    pub returned_amount: u64,
+    pub vested_amount: u64,
+}
+
+#[event]
+pub struct LockAmountIncreased {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub lock_id: u64,
+    // Tokens credited to the lock, after the protocol's token cut and any transfer fee.
+    pub amount: u64,
+    pub fee_amount: u64,
+    pub new_amount: u64,
+}
+
+#[event]
+pub struct LockExtended {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub lock_id: u64,
+    pub old_unlock_timestamp: i64,
+    pub new_unlock_timestamp: i64,
+}
+
+#[event]
+pub struct LockOwnershipTransferStarted {
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+    pub mint: Pubkey,
+    pub lock_id: u64,
+}
+
+#[event]
+pub struct LockOwnershipTransferred {
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub mint: Pubkey,
+    pub lock_id: u64,
+}
+
+#[event]
+pub struct LockSplit {
+    pub owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub mint: Pubkey,
+    pub lock_id: u64,
+    pub new_lock_id: u64,
+    pub amount: u64,
+    pub unlock_timestamp: i64,
+}
+
+#[event]
+pub struct LocksMerged {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub lock_id: u64,
+    pub source_lock_id: u64,
+    pub amount: u64,
+    pub new_amount: u64,
+    pub unlock_timestamp: i64,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub params: ConfigParams,
+}
+
+#[event]
+pub struct AdminTransferStarted {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct FeeDiscountAdded {
+    pub subject: Pubkey,
+    pub discount_bps: u16,
+}
+
+#[event]
+pub struct FeeDiscountRemoved {
+    pub subject: Pubkey,
+}
+
+#[event]
+pub struct ReferrerRegistered {
+    pub referrer: Pubkey,
+}
+
+#[event]
+pub struct ProgramPaused {
+    pub authority: Pubkey,
+    pub withdrawals_paused: bool,
+}
+
+#[event]
+pub struct ProgramUnpaused {
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct MintEntryAdded {
+    pub mint: Pubkey,
+    pub allowed: bool,
+}
+
+#[event]
+pub struct MintEntryRemoved {
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct LockSynced {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub lock_id: u64,
+    // Vault balance found on top of the recorded amount.
+    pub surplus: u64,
+    // `true` when the surplus was sent to the owner, `false` when it was credited to the lock.
+    pub swept: bool,
+    // Protocol token cut taken from a credited surplus.
+    pub fee_amount: u64,
+    pub new_amount: u64,
+}
+
+#[event]
+pub struct LockMigrated {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub lock_id: u64,
+}
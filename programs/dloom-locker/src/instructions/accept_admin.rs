@@ -0,0 +1,31 @@
+// FILE: programs/dloom_locker/src/instructions/accept_admin.rs
+use anchor_lang::prelude::*;
+use crate::{errors::LockerError, events::AdminTransferred, state::Config};
+
+pub fn handle_accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let previous_admin = config.admin;
+
+    config.admin = ctx.accounts.new_admin.key();
+    config.pending_admin = Pubkey::default();
+
+    emit!(AdminTransferred {
+        previous_admin,
+        new_admin: config.admin,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub new_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = config.pending_admin == new_admin.key() @ LockerError::NotPendingAdmin,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
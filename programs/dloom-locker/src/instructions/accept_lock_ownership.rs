@@ -0,0 +1,49 @@
+// FILE: programs/dloom_locker/src/instructions/accept_lock_ownership.rs
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::{errors::LockerError, events::LockOwnershipTransferred, state::{Config, LockRecord}};
+
+pub fn handle_accept_lock_ownership(ctx: Context<AcceptLockOwnership>, lock_id: u64) -> Result<()> {
+    let lock_record = &mut ctx.accounts.lock_record;
+    let previous_owner = lock_record.owner;
+
+    lock_record.owner = ctx.accounts.new_owner.key();
+    lock_record.pending_owner = Pubkey::default();
+
+    emit!(LockOwnershipTransferred {
+        previous_owner,
+        new_owner: lock_record.owner,
+        mint: lock_record.mint,
+        lock_id,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(lock_id: u64)]
+pub struct AcceptLockOwnership<'info> {
+    pub new_owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ LockerError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        constraint = lock_record.pending_owner == new_owner.key() @ LockerError::NotPendingOwner,
+        seeds = [
+            b"lock_record",
+            lock_record.original_owner.as_ref(),
+            token_mint.key().as_ref(),
+            &lock_id.to_le_bytes()
+        ],
+        bump = lock_record.bump
+    )]
+    pub lock_record: Account<'info, LockRecord>,
+}
@@ -0,0 +1,48 @@
+// FILE: programs/dloom_locker/src/instructions/add_fee_discount.rs
+use anchor_lang::prelude::*;
+use crate::{errors::LockerError, events::FeeDiscountAdded, state::{Config, FeeDiscount, MAX_DISCOUNT_BPS}};
+
+pub fn handle_add_fee_discount(ctx: Context<AddFeeDiscount>, subject: Pubkey, discount_bps: u16) -> Result<()> {
+    require!(
+        discount_bps > 0 && discount_bps <= MAX_DISCOUNT_BPS,
+        LockerError::InvalidConfig
+    );
+
+    let fee_discount = &mut ctx.accounts.fee_discount;
+    fee_discount.bump = ctx.bumps.fee_discount;
+    fee_discount.subject = subject;
+    fee_discount.discount_bps = discount_bps;
+
+    emit!(FeeDiscountAdded {
+        subject,
+        discount_bps,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(subject: Pubkey)]
+pub struct AddFeeDiscount<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ LockerError::Unauthorized,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // One entry per wallet or mint; remove it first to change the discount.
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + FeeDiscount::INIT_SPACE,
+        seeds = [b"fee_discount", subject.as_ref()],
+        bump
+    )]
+    pub fee_discount: Account<'info, FeeDiscount>,
+
+    pub system_program: Program<'info, System>,
+}
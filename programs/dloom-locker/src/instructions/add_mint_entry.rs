@@ -0,0 +1,43 @@
+// FILE: programs/dloom_locker/src/instructions/add_mint_entry.rs
+use anchor_lang::prelude::*;
+use crate::{errors::LockerError, events::MintEntryAdded, state::{Config, MintEntry}};
+
+pub fn handle_add_mint_entry(ctx: Context<AddMintEntry>, mint: Pubkey, allowed: bool) -> Result<()> {
+    let mint_entry = &mut ctx.accounts.mint_entry;
+    mint_entry.bump = ctx.bumps.mint_entry;
+    mint_entry.mint = mint;
+    mint_entry.allowed = allowed;
+
+    emit!(MintEntryAdded {
+        mint,
+        allowed,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct AddMintEntry<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ LockerError::Unauthorized,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    // One entry per mint; remove it first to flip it between allowed and denied.
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + MintEntry::INIT_SPACE,
+        seeds = [b"mint_entry", mint.as_ref()],
+        bump
+    )]
+    pub mint_entry: Account<'info, MintEntry>,
+
+    pub system_program: Program<'info, System>,
+}
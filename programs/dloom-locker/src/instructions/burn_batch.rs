@@ -1,7 +1,7 @@
 // FILE: programs/dloom_locker/src/instructions/burn_batch.rs
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{self, Burn, TokenInterface};
-use crate::{errors::LockerError, events::BatchTokensBurned};
+use crate::{errors::LockerError, events::BatchTokensBurned, state::Config};
 
 pub fn handle_burn_batch<'info>(
     ctx: Context<'_, '_, '_, 'info, BurnBatch<'info>>, 
@@ -53,5 +53,13 @@ pub fn handle_burn_batch<'info>(
 pub struct BurnBatch<'info> {
     #[account(mut)]
     pub burner: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ LockerError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
     pub token_program: Interface<'info, TokenInterface>,
 }
\ No newline at end of file
@@ -1,25 +1,24 @@
 // FILE: programs/dloom_locker/src/instructions/burn_from_lock.rs
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{self, Burn, Mint, TokenAccount, TokenInterface};
-use crate::{errors::LockerError, events::LockedTokensBurned, state::LockRecord};
+use anchor_spl::token_2022::Token2022;
+use crate::{errors::LockerError, events::LockedTokensBurned, receipt::refresh_receipt_metadata, state::{Config, LockRecord, MintStats}};
 
 pub fn handle_burn_from_lock(ctx: Context<BurnFromLock>, amount: u64, lock_id: u64) -> Result<()> {
+    ctx.accounts
+        .lock_record
+        .check_authority(
+            &ctx.accounts.owner.key(),
+            ctx.accounts.receipt_mint.as_ref(),
+            ctx.accounts.receipt_token_account.as_deref(),
+        )?;
+
     require!(amount > 0, LockerError::ZeroAmount);
     require!(ctx.accounts.lock_record.amount >= amount, LockerError::BurnAmountExceedsLocked);
 
     // Prepare seeds for signing
-    let owner_key = ctx.accounts.owner.key();
-    let mint_key = ctx.accounts.token_mint.key();
-    let lock_id_bytes = lock_id.to_le_bytes();
-    let bump = ctx.accounts.lock_record.bump;
-
-    let seeds = &[
-        b"lock_record".as_ref(),
-        owner_key.as_ref(),
-        mint_key.as_ref(),
-        lock_id_bytes.as_ref(),
-        &[bump],
-    ];
+    let lock_seeds = ctx.accounts.lock_record.signer_seeds();
+    let seeds = lock_seeds.as_seeds();
     let signer_seeds = &[&seeds[..]];
 
     // Burn from Vault
@@ -38,6 +37,12 @@ pub fn handle_burn_from_lock(ctx: Context<BurnFromLock>, amount: u64, lock_id: u
 
     // Update State
     ctx.accounts.lock_record.amount = ctx.accounts.lock_record.amount.checked_sub(amount).unwrap();
+    ctx.accounts.mint_stats.release(amount)?;
+    refresh_receipt_metadata(
+        &ctx.accounts.lock_record,
+        ctx.accounts.receipt_mint.as_ref(),
+        ctx.accounts.receipt_token_program.as_ref(),
+    )?;
 
     emit!(LockedTokensBurned {
         owner: ctx.accounts.owner.key(),
@@ -52,19 +57,26 @@ pub fn handle_burn_from_lock(ctx: Context<BurnFromLock>, amount: u64, lock_id: u
 #[derive(Accounts)]
 #[instruction(amount: u64, lock_id: u64)]
 pub struct BurnFromLock<'info> {
+    // The lock owner, or the receipt holder for tokenized locks.
     #[account(mut)]
     pub owner: Signer<'info>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ LockerError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
     #[account(mut)]
     pub token_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
-        has_one = owner,
         has_one = vault,
         seeds = [
             b"lock_record", 
-            owner.key().as_ref(), 
+            lock_record.original_owner.as_ref(),
             token_mint.key().as_ref(), 
             &lock_id.to_le_bytes()
         ],
@@ -79,5 +91,17 @@ pub struct BurnFromLock<'info> {
     )]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
+    #[account(
+        mut,
+        seeds = [b"mint_stats", token_mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
     pub token_program: Interface<'info, TokenInterface>,
+
+    pub receipt_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+    pub receipt_token_program: Option<Program<'info, Token2022>>,
 }
\ No newline at end of file
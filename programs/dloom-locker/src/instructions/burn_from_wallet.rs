@@ -1,7 +1,7 @@
 // FILE: programs/dloom_locker/src/instructions/burn_from_wallet.rs
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{self, Burn, Mint, TokenAccount, TokenInterface};
-use crate::{errors::LockerError, events::TokensBurned};
+use crate::{errors::LockerError, events::TokensBurned, state::Config};
 
 pub fn handle_burn_from_wallet(ctx: Context<BurnFromWallet>, amount: u64) -> Result<()> {
     require!(amount > 0, LockerError::ZeroAmount);
@@ -32,6 +32,13 @@ pub struct BurnFromWallet<'info> {
     #[account(mut)]
     pub burner: Signer<'info>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ LockerError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
     #[account(mut)]
     pub token_mint: InterfaceAccount<'info, Mint>,
 
@@ -6,9 +6,19 @@ use anchor_spl::token_2022::spl_token_2022::{
     extension::{transfer_fee::TransferFeeAmount, StateWithExtensions},
     state::Account as Token2022Account,
 };
-use crate::{errors::LockerError, state::LockRecord};
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_2022_extensions::{self, HarvestWithheldTokensToMint};
+use crate::{errors::LockerError, receipt::redeem_receipt, state::{Config, LockRecord, MintStats}};
+
+pub fn handle_close_vault(ctx: Context<CloseVault>, _lock_id: u64) -> Result<()> {
+    ctx.accounts
+        .lock_record
+        .check_authority(
+            &ctx.accounts.owner.key(),
+            ctx.accounts.receipt_mint.as_ref(),
+            ctx.accounts.receipt_token_account.as_deref(),
+        )?;
 
-pub fn handle_close_vault(ctx: Context<CloseVault>, lock_id: u64) -> Result<()> {
     require!(ctx.accounts.lock_record.amount == 0, LockerError::ZeroAmount);
 
     // Check for withheld fees in Token-2022
@@ -27,22 +37,31 @@ pub fn handle_close_vault(ctx: Context<CloseVault>, lock_id: u64) -> Result<()>
         }
     };
 
+    // Harvesting withheld fees to the mint is permissionless and leaves the vault closable
     if has_fees {
-        return err!(LockerError::CannotCloseWithheldFees); 
+        token_2022_extensions::harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                HarvestWithheldTokensToMint {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                },
+            ),
+            vec![ctx.accounts.vault.to_account_info()],
+        )?;
     }
 
-    let owner_key = ctx.accounts.owner.key();
-    let mint_key = ctx.accounts.token_mint.key();
-    let bump = ctx.accounts.lock_record.bump;
-    let lock_id_bytes = lock_id.to_le_bytes(); 
-
-    let seeds = &[
-        b"lock_record".as_ref(),
-        owner_key.as_ref(),
-        mint_key.as_ref(),
-        lock_id_bytes.as_ref(),
-        &[bump],
-    ];
+    // A receipt still outstanding (e.g. after burning the whole lock) is burned with it
+    redeem_receipt(
+        &mut ctx.accounts.lock_record,
+        &ctx.accounts.owner,
+        ctx.accounts.receipt_mint.as_ref(),
+        ctx.accounts.receipt_token_account.as_ref(),
+        ctx.accounts.receipt_token_program.as_ref(),
+    )?;
+
+    let lock_seeds = ctx.accounts.lock_record.signer_seeds();
+    let seeds = lock_seeds.as_seeds();
     let signer_seeds = &[&seeds[..]];
 
     token_interface::close_account(CpiContext::new_with_signer(
@@ -54,6 +73,7 @@ pub fn handle_close_vault(ctx: Context<CloseVault>, lock_id: u64) -> Result<()>
         },
         signer_seeds,
     ))?;
+    ctx.accounts.mint_stats.remove_lock()?;
 
     msg!("Vault closed successfully.");
 
@@ -63,16 +83,23 @@ pub fn handle_close_vault(ctx: Context<CloseVault>, lock_id: u64) -> Result<()>
 #[derive(Accounts)]
 #[instruction(lock_id: u64)]
 pub struct CloseVault<'info> {
+    // The lock owner, or the receipt holder for tokenized locks.
     #[account(mut)]
     pub owner: Signer<'info>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.withdrawals_paused @ LockerError::WithdrawalsPaused
+    )]
+    pub config: Account<'info, Config>,
+
     #[account(
         mut,
         close = owner,
-        has_one = owner,
         seeds = [
             b"lock_record", 
-            owner.key().as_ref(), 
+            lock_record.original_owner.as_ref(),
             token_mint.key().as_ref(),
             &lock_id.to_le_bytes()
         ],
@@ -83,7 +110,22 @@ pub struct CloseVault<'info> {
     #[account(mut, address = lock_record.vault)]
     pub vault: InterfaceAccount<'info, TokenAccount>,
 
+    // Writable so withheld transfer fees can be harvested into it.
+    #[account(mut)]
     pub token_mint: InterfaceAccount<'info, Mint>,
 
+    #[account(
+        mut,
+        seeds = [b"mint_stats", token_mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
     pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(mut)]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub receipt_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub receipt_token_program: Option<Program<'info, Token2022>>,
 }
\ No newline at end of file
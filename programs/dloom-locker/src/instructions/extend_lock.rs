@@ -0,0 +1,89 @@
+// FILE: programs/dloom_locker/src/instructions/extend_lock.rs
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use anchor_spl::token_2022::Token2022;
+use crate::{errors::LockerError, events::LockExtended, receipt::refresh_receipt_metadata, state::{Config, LockRecord, MintStats}};
+
+pub fn handle_extend_lock(ctx: Context<ExtendLock>, lock_id: u64, new_unlock_timestamp: i64) -> Result<()> {
+    ctx.accounts
+        .lock_record
+        .check_authority(
+            &ctx.accounts.owner.key(),
+            ctx.accounts.receipt_mint.as_ref(),
+            ctx.accounts.receipt_token_account.as_deref(),
+        )?;
+
+    let old_unlock_timestamp = ctx.accounts.lock_record.unlock_timestamp;
+
+    require!(
+        new_unlock_timestamp > old_unlock_timestamp,
+        LockerError::UnlockDateNotLater
+    );
+    let current_timestamp = Clock::get()?.unix_timestamp;
+    require!(
+        new_unlock_timestamp - current_timestamp <= ctx.accounts.config.max_lock_duration,
+        LockerError::LockDurationTooLong
+    );
+    // Tranched and custom schedules are pinned to their own end date.
+    require!(ctx.accounts.lock_record.schedule.is_extendable(), LockerError::ScheduleNotExtendable);
+
+    ctx.accounts.lock_record.unlock_timestamp = new_unlock_timestamp;
+    ctx.accounts.mint_stats.track_unlock(new_unlock_timestamp);
+
+    refresh_receipt_metadata(
+        &ctx.accounts.lock_record,
+        ctx.accounts.receipt_mint.as_ref(),
+        ctx.accounts.receipt_token_program.as_ref(),
+    )?;
+
+    let lock_record = &ctx.accounts.lock_record;
+    emit!(LockExtended {
+        owner: lock_record.owner,
+        mint: lock_record.mint,
+        lock_id,
+        old_unlock_timestamp,
+        new_unlock_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(lock_id: u64)]
+pub struct ExtendLock<'info> {
+    // The lock owner, or the receipt holder for tokenized locks.
+    pub owner: Signer<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ LockerError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"lock_record",
+            lock_record.original_owner.as_ref(),
+            token_mint.key().as_ref(),
+            &lock_id.to_le_bytes()
+        ],
+        bump = lock_record.bump
+    )]
+    pub lock_record: Account<'info, LockRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_stats", token_mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    pub receipt_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+    pub receipt_token_program: Option<Program<'info, Token2022>>,
+}
@@ -0,0 +1,175 @@
+// FILE: programs/dloom_locker/src/instructions/increase_lock_amount.rs
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use anchor_spl::token_2022::Token2022;
+use crate::{errors::LockerError, events::LockAmountIncreased, receipt::refresh_receipt_metadata, state::{best_discount_bps, discounted_fee, Config, FeeDiscount, LockRecord, LockSchedule, MintStats}, transfer_hook};
+
+pub fn handle_increase_lock_amount<'info>(
+    ctx: Context<'_, '_, '_, 'info, IncreaseLockAmount<'info>>,
+    lock_id: u64,
+    amount: u64,
+) -> Result<()> {
+    ctx.accounts
+        .lock_record
+        .check_authority(
+            &ctx.accounts.owner.key(),
+            ctx.accounts.receipt_mint.as_ref(),
+            ctx.accounts.receipt_token_account.as_deref(),
+        )?;
+
+    require!(amount > 0, LockerError::ZeroAmount);
+    // The grantor could claw back anything added to a revocable grant.
+    require!(!ctx.accounts.lock_record.revocable, LockerError::RevocableLock);
+    // Vesting curves scale with the total, so added tokens would count as already vested.
+    require!(ctx.accounts.lock_record.schedule == LockSchedule::Cliff, LockerError::NotCliffLock);
+
+    // Top-ups pay the same token cut as new locks, so a small lock cannot be grown fee-free.
+    let discount_bps = best_discount_bps(&[
+        ctx.accounts.owner_fee_discount.as_deref(),
+        ctx.accounts.mint_fee_discount.as_deref(),
+    ]);
+    let fee_amount = discounted_fee(ctx.accounts.config.lock_fee_amount(amount)?, discount_bps);
+    let lock_amount = amount.checked_sub(fee_amount).ok_or(LockerError::MathOverflow)?;
+    require!(lock_amount > 0, LockerError::ZeroAmount);
+    if fee_amount > 0 {
+        let Some(treasury_token_account) = &ctx.accounts.treasury_token_account else {
+            return err!(LockerError::TreasuryAccountMissing);
+        };
+        transfer_hook::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: treasury_token_account.to_account_info(),
+                    authority: ctx.accounts.owner.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            fee_amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+    }
+
+    // 1. Check balance BEFORE transfer
+    ctx.accounts.vault.reload()?;
+    let balance_before = ctx.accounts.vault.amount;
+
+    // 2. Perform Transfer
+    transfer_hook::transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_token_account.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.owner.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+            },
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        lock_amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    ctx.accounts.vault.reload()?;
+    let balance_after = ctx.accounts.vault.amount;
+
+    // Credit only what actually arrived (Token-2022 transfer fees)
+    let actual_amount = balance_after
+        .checked_sub(balance_before)
+        .ok_or(LockerError::MathOverflow)?;
+
+    ctx.accounts.lock_record.amount = ctx
+        .accounts
+        .lock_record
+        .amount
+        .checked_add(actual_amount)
+        .ok_or(LockerError::MathOverflow)?;
+    ctx.accounts.mint_stats.deposit(actual_amount)?;
+
+    refresh_receipt_metadata(
+        &ctx.accounts.lock_record,
+        ctx.accounts.receipt_mint.as_ref(),
+        ctx.accounts.receipt_token_program.as_ref(),
+    )?;
+
+    let lock_record = &ctx.accounts.lock_record;
+    emit!(LockAmountIncreased {
+        owner: lock_record.owner,
+        mint: lock_record.mint,
+        lock_id,
+        amount: actual_amount,
+        fee_amount,
+        new_amount: lock_record.amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(lock_id: u64, amount: u64)]
+pub struct IncreaseLockAmount<'info> {
+    // The lock owner, or the receipt holder for tokenized locks.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ LockerError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    // Required when the config takes a token cut: the treasury's account for this mint.
+    #[account(
+        mut,
+        constraint = treasury_token_account.mint == token_mint.key() @ LockerError::TreasuryAccountMissing,
+        constraint = treasury_token_account.owner == config.treasury @ LockerError::TreasuryAccountMissing
+    )]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // Optional: fee discount entries for the owner and for the mint.
+    #[account(seeds = [b"fee_discount", owner.key().as_ref()], bump = owner_fee_discount.bump)]
+    pub owner_fee_discount: Option<Box<Account<'info, FeeDiscount>>>,
+
+    #[account(seeds = [b"fee_discount", token_mint.key().as_ref()], bump = mint_fee_discount.bump)]
+    pub mint_fee_discount: Option<Box<Account<'info, FeeDiscount>>>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        seeds = [
+            b"lock_record",
+            lock_record.original_owner.as_ref(),
+            token_mint.key().as_ref(),
+            &lock_id.to_le_bytes()
+        ],
+        bump = lock_record.bump
+    )]
+    pub lock_record: Account<'info, LockRecord>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.mint == token_mint.key()
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_stats", token_mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub receipt_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+    pub receipt_token_program: Option<Program<'info, Token2022>>,
+}
@@ -0,0 +1,42 @@
+// FILE: programs/dloom_locker/src/instructions/initialize_config.rs
+use anchor_lang::prelude::*;
+use crate::{errors::LockerError, events::ConfigUpdated, program::DloomLocker, state::{Config, ConfigParams}};
+
+pub fn handle_initialize_config(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.bump = ctx.bumps.config;
+    config.admin = ctx.accounts.admin.key();
+    config.pending_admin = Pubkey::default();
+    config.apply(&params)?;
+
+    emit!(ConfigUpdated {
+        admin: config.admin,
+        params,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    // Only the program's upgrade authority can create the config, and becomes its first admin.
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, DloomLocker>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ LockerError::Unauthorized)]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,43 +1,171 @@
 // FILE: programs/dloom_locker/src/instructions/lock_tokens.rs
-use crate::{errors::LockerError, events::TokensLocked, state::LockRecord};
+use crate::{errors::LockerError, events::TokensLocked, mint_risk::mint_risk_flags, receipt::{initialize_receipt_metadata, mint_receipt}, state::{best_discount_bps, discounted_fee, Config, FeeDiscount, LockCounter, LockRecord, LockSchedule, MintEntry, MintStats, ReferralStats}, transfer_hook};
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
 
-const MAX_LOCK_DURATION: i64 = 5 * 365 * 24 * 60 * 60;
-
-pub fn handle_lock_tokens(
-    ctx: Context<LockTokens>,
+pub fn handle_lock_tokens<'info>(
+    ctx: Context<'_, '_, '_, 'info, LockTokens<'info>>,
     amount: u64,
     unlock_timestamp: i64,
-    lock_id: u64, 
-) -> Result<()> {
+    lock_id: Option<u64>,
+    schedule: LockSchedule,
+    revocable: bool,
+) -> Result<u64> {
     require!(amount > 0, LockerError::ZeroAmount);
+    // A self-lock the owner could revoke would look locked while it can be emptied at any time.
+    require!(
+        !revocable || ctx.accounts.funder.key() != ctx.accounts.beneficiary.key(),
+        LockerError::SelfRevocableLock
+    );
+
+    // Without an explicit id the beneficiary's counter assigns the next one. Only the beneficiary
+    // may pick an id, so nobody else can take or skip past the ids its counter hands out.
+    require!(
+        lock_id.is_none() || ctx.accounts.funder.key() == ctx.accounts.beneficiary.key(),
+        LockerError::LockIdNotAllowed
+    );
+    ctx.accounts.lock_counter.bump = ctx.bumps.lock_counter;
+    let lock_id = ctx.accounts.lock_counter.assign(lock_id)?;
+
     let current_timestamp = Clock::get()?.unix_timestamp;
     require!(
         unlock_timestamp > current_timestamp,
         LockerError::UnlockDateInPast
     );
+    let config = &ctx.accounts.config;
+    config.check_lock_duration(unlock_timestamp, current_timestamp)?;
     require!(
-        unlock_timestamp - current_timestamp <= MAX_LOCK_DURATION,
-        LockerError::LockDurationTooLong
+        config.vesting_enabled || schedule == LockSchedule::Cliff,
+        LockerError::FeatureDisabled
     );
+    schedule.validate(amount, unlock_timestamp)?;
+    config.check_mint_allowed(MintEntry::load(&ctx.accounts.mint_entry)?.as_ref())?;
+    let risk_flags = mint_risk_flags(&ctx.accounts.token_mint.to_account_info())?;
+    require!(risk_flags & config.rejected_risk_flags == 0, LockerError::RiskyMint);
 
-    // 1. Check balance BEFORE transfer
+    // The best discount among the funder's and the mint's entries applies.
+    let discount_bps = best_discount_bps(&[
+        ctx.accounts.funder_fee_discount.as_deref(),
+        ctx.accounts.mint_fee_discount.as_deref(),
+    ]);
+    let fee_lamports = discounted_fee(config.lock_fee_lamports, discount_bps);
+    let fee_amount = discounted_fee(config.lock_fee_amount(amount)?, discount_bps);
+    let lock_amount = amount.checked_sub(fee_amount).ok_or(LockerError::MathOverflow)?;
+    require!(lock_amount > 0, LockerError::ZeroAmount);
+
+    // A registered referrer earns a share of both the SOL fee and the token cut. Self-referrals
+    // are refused.
+    let (referral_lamports, referral_amount) = match (&ctx.accounts.referrer, &ctx.accounts.referral_stats) {
+        (None, None) => (0, 0),
+        (Some(referrer), Some(referral_stats)) => {
+            require_keys_eq!(referral_stats.referrer, referrer.key(), LockerError::InvalidReferrer);
+            require_keys_neq!(referrer.key(), ctx.accounts.funder.key(), LockerError::InvalidReferrer);
+            (config.referral_share(fee_lamports), config.referral_share(fee_amount))
+        }
+        _ => return err!(LockerError::InvalidReferrer),
+    };
+    let treasury_lamports = fee_lamports - referral_lamports;
+    let treasury_amount = fee_amount - referral_amount;
+
+    // 1. Collect the protocol fees
+    if treasury_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.funder.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            ),
+            treasury_lamports,
+        )?;
+    }
+    if let (Some(referrer), Some(referral_stats)) =
+        (&ctx.accounts.referrer, &mut ctx.accounts.referral_stats)
+    {
+        if referral_lamports > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.funder.to_account_info(),
+                        to: referrer.to_account_info(),
+                    },
+                ),
+                referral_lamports,
+            )?;
+        }
+        referral_stats.referred_locks = referral_stats
+            .referred_locks
+            .checked_add(1)
+            .ok_or(LockerError::MathOverflow)?;
+        referral_stats.total_rewards_lamports = referral_stats
+            .total_rewards_lamports
+            .checked_add(referral_lamports)
+            .ok_or(LockerError::MathOverflow)?;
+    }
+    if referral_amount > 0 {
+        let (Some(referrer), Some(referrer_token_account)) =
+            (&ctx.accounts.referrer, &ctx.accounts.referrer_token_account)
+        else {
+            return err!(LockerError::InvalidReferrer);
+        };
+        require_keys_eq!(referrer_token_account.owner, referrer.key(), LockerError::InvalidReferrer);
+        transfer_hook::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: referrer_token_account.to_account_info(),
+                    authority: ctx.accounts.funder.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            referral_amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+    }
+    if treasury_amount > 0 {
+        let Some(treasury_token_account) = &ctx.accounts.treasury_token_account else {
+            return err!(LockerError::TreasuryAccountMissing);
+        };
+        transfer_hook::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    to: treasury_token_account.to_account_info(),
+                    authority: ctx.accounts.funder.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                },
+            )
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            treasury_amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+    }
+
+    // 2. Check balance BEFORE transfer
     ctx.accounts.vault.reload()?;
     let balance_before = ctx.accounts.vault.amount;
 
-    // 2. Perform Transfer
-    token_interface::transfer_checked(
+    // 3. Perform Transfer (remaining accounts carry any transfer hook's extra accounts)
+    transfer_hook::transfer_checked(
         CpiContext::new(
             ctx.accounts.token_program.to_account_info(),
             TransferChecked {
                 from: ctx.accounts.user_token_account.to_account_info(),
                 to: ctx.accounts.vault.to_account_info(),
-                authority: ctx.accounts.owner.to_account_info(),
+                authority: ctx.accounts.funder.to_account_info(),
                 mint: ctx.accounts.token_mint.to_account_info(),
             },
-        ),
-        amount,
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        lock_amount,
         ctx.accounts.token_mint.decimals,
     )?;
 
@@ -51,40 +179,170 @@ pub fn handle_lock_tokens(
 
     let lock_record = &mut ctx.accounts.lock_record;
     lock_record.bump = ctx.bumps.lock_record;
-    lock_record.owner = ctx.accounts.owner.key();
+    lock_record.owner = ctx.accounts.beneficiary.key();
     lock_record.mint = ctx.accounts.token_mint.key();
     lock_record.vault = ctx.accounts.vault.key();
     lock_record.amount = actual_amount;
     lock_record.unlock_timestamp = unlock_timestamp;
-    lock_record.id = lock_id; 
+    lock_record.id = lock_id;
+    lock_record.schedule = schedule;
+    lock_record.withdrawn = 0;
+    lock_record.funder = ctx.accounts.funder.key();
+    lock_record.revocable = revocable;
+    lock_record.original_owner = lock_record.owner;
+    lock_record.pending_owner = Pubkey::default();
+    lock_record.receipt_mint = Pubkey::default();
+    lock_record.risk_flags = risk_flags;
+
+    let mint_stats = &mut ctx.accounts.mint_stats;
+    mint_stats.bump = ctx.bumps.mint_stats;
+    mint_stats.mint = ctx.accounts.token_mint.key();
+    mint_stats.add_lock(unlock_timestamp)?;
+    mint_stats.deposit(actual_amount)?;
+
+    // 4. Optionally tokenize the lock with a one-of-one receipt carrying the lock's metadata
+    if let Some(receipt_mint) = &ctx.accounts.receipt_mint {
+        let (Some(receipt_token_account), Some(receipt_token_program)) = (
+            &ctx.accounts.receipt_token_account,
+            &ctx.accounts.receipt_token_program,
+        ) else {
+            return err!(LockerError::ReceiptAccountsMissing);
+        };
+        require!(ctx.accounts.config.receipts_enabled, LockerError::FeatureDisabled);
+        // A receipt buyer could not see that the grantor keeps the right to revoke.
+        require!(!revocable, LockerError::RevocableReceipt);
+
+        let lock_seeds = ctx.accounts.lock_record.signer_seeds();
+        let seeds = lock_seeds.as_seeds();
+
+        initialize_receipt_metadata(
+            receipt_token_program,
+            receipt_mint,
+            &ctx.accounts.lock_record,
+            ctx.accounts.funder.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            &[&seeds[..]],
+        )?;
+        mint_receipt(
+            receipt_token_program,
+            receipt_mint,
+            receipt_token_account,
+            ctx.accounts.lock_record.to_account_info(),
+            &[&seeds[..]],
+        )?;
+        ctx.accounts.lock_record.receipt_mint = receipt_mint.key();
+    }
 
+    let lock_record = &ctx.accounts.lock_record;
     emit!(TokensLocked {
         owner: lock_record.owner,
+        lock_id,
+        funder: lock_record.funder,
         mint: lock_record.mint,
         amount: actual_amount,
         unlock_timestamp: lock_record.unlock_timestamp,
+        schedule: lock_record.schedule.clone(),
+        revocable,
+        receipt_mint: lock_record.receipt_mint,
+        fee_lamports,
+        fee_amount,
+        referrer: ctx.accounts.referrer.as_ref().map_or(Pubkey::default(), |referrer| referrer.key()),
+        referral_lamports,
+        referral_amount,
+        risk_flags,
     });
 
-    Ok(())
+    Ok(lock_id)
 }
 
 #[derive(Accounts)]
-#[instruction(amount: u64, unlock_timestamp: i64, lock_id: u64)]
+#[instruction(amount: u64, unlock_timestamp: i64, lock_id: Option<u64>, schedule: LockSchedule, revocable: bool)]
 pub struct LockTokens<'info> {
+    // Pays for the accounts and provides the tokens.
     #[account(mut)]
-    pub owner: Signer<'info>,
-    
+    pub funder: Signer<'info>,
+
+    /// CHECK: Any wallet can be a beneficiary; it is only used as a PDA seed and recorded as the lock owner.
+    pub beneficiary: UncheckedAccount<'info>,
+
     pub token_mint: InterfaceAccount<'info, Mint>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = treasury,
+        constraint = !config.paused @ LockerError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Receives the SOL fee; checked against the config.
+    #[account(mut)]
+    pub treasury: UncheckedAccount<'info>,
+
+    // Required when the config takes a token cut: the treasury's account for this mint.
+    #[account(
+        mut,
+        constraint = treasury_token_account.mint == token_mint.key() @ LockerError::TreasuryAccountMissing,
+        constraint = treasury_token_account.owner == treasury.key() @ LockerError::TreasuryAccountMissing
+    )]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: The mint's allow/deny entry PDA. It may not exist; `MintEntry::load` only reads it when it does.
+    #[account(seeds = [b"mint_entry", token_mint.key().as_ref()], bump)]
+    pub mint_entry: UncheckedAccount<'info>,
+
+    // Optional: fee discount entries for the funder and for the mint.
+    #[account(seeds = [b"fee_discount", funder.key().as_ref()], bump = funder_fee_discount.bump)]
+    pub funder_fee_discount: Option<Box<Account<'info, FeeDiscount>>>,
+
+    #[account(seeds = [b"fee_discount", token_mint.key().as_ref()], bump = mint_fee_discount.bump)]
+    pub mint_fee_discount: Option<Box<Account<'info, FeeDiscount>>>,
+
+    /// CHECK: Optional referrer wallet, paid its share of the fees; must match `referral_stats`.
+    #[account(mut)]
+    pub referrer: Option<UncheckedAccount<'info>>,
+
+    // Required when a referrer earns part of the token cut: the referrer's account for this mint.
+    #[account(
+        mut,
+        constraint = referrer_token_account.mint == token_mint.key() @ LockerError::InvalidReferrer
+    )]
+    pub referrer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        seeds = [b"referral", referral_stats.referrer.as_ref()],
+        bump = referral_stats.bump
+    )]
+    pub referral_stats: Option<Box<Account<'info, ReferralStats>>>,
+
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = 8 + LockCounter::INIT_SPACE,
+        seeds = [b"lock_counter", beneficiary.key().as_ref()],
+        bump
+    )]
+    pub lock_counter: Box<Account<'info, LockCounter>>,
+
+    #[account(
+        init_if_needed,
+        payer = funder,
+        space = 8 + MintStats::INIT_SPACE,
+        seeds = [b"mint_stats", token_mint.key().as_ref()],
+        bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
     #[account(
         init,
-        payer = owner,
-        space = 8 + 1 + 32 + 32 + 32 + 8 + 8 + 8, 
+        payer = funder,
+        space = LockRecord::space(&schedule),
         seeds = [
             b"lock_record", 
-            owner.key().as_ref(), 
+            beneficiary.key().as_ref(),
             token_mint.key().as_ref(), 
-            &lock_id.to_le_bytes()
+            &lock_id.unwrap_or(lock_counter.next_id).to_le_bytes()
         ],
         bump
     )]
@@ -92,7 +350,7 @@ pub struct LockTokens<'info> {
 
     #[account(
         init,
-        payer = owner,
+        payer = funder,
         seeds = [b"vault", lock_record.key().as_ref()],
         bump,
         token::mint = token_mint,
@@ -107,6 +365,32 @@ pub struct LockTokens<'info> {
     )]
     pub user_token_account: InterfaceAccount<'info, TokenAccount>,
 
+    // Optional: pass the receipt accounts to tokenize the lock as a one-of-one Token-2022 receipt.
+    #[account(
+        init,
+        payer = funder,
+        seeds = [b"receipt", lock_record.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = lock_record,
+        mint::token_program = receipt_token_program,
+        extensions::metadata_pointer::authority = lock_record,
+        extensions::metadata_pointer::metadata_address = receipt_mint,
+    )]
+    pub receipt_mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        init,
+        payer = funder,
+        associated_token::mint = receipt_mint,
+        associated_token::authority = beneficiary,
+        associated_token::token_program = receipt_token_program,
+    )]
+    pub receipt_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub receipt_token_program: Option<Program<'info, Token2022>>,
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
     pub rent: Sysvar<'info, Rent>,
@@ -0,0 +1,192 @@
+// FILE: programs/dloom_locker/src/instructions/merge_locks.rs
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+use anchor_spl::token_2022::spl_token_2022::extension::BaseStateWithExtensions;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{transfer_fee::TransferFeeAmount, StateWithExtensions},
+    state::Account as Token2022Account,
+};
+use anchor_spl::token_2022_extensions::{self, HarvestWithheldTokensToMint};
+use crate::{errors::LockerError, events::LocksMerged, state::{Config, LockRecord, LockSchedule, MintStats}, transfer_hook};
+
+pub fn handle_merge_locks<'info>(
+    ctx: Context<'_, '_, '_, 'info, MergeLocks<'info>>,
+    lock_id: u64,
+    source_lock_id: u64,
+) -> Result<()> {
+    // Only plain cliff locks can be folded together; vesting curves, grantor rights and receipts don't combine.
+    for record in [&ctx.accounts.lock_record, &ctx.accounts.source_lock_record] {
+        require!(
+            record.schedule == LockSchedule::Cliff && !record.revocable && !record.is_tokenized(),
+            LockerError::IncompatibleLocks
+        );
+    }
+
+    // The source vault gets closed, so any withheld fees are harvested to the mint first
+    let has_fees = {
+        let vault_info = ctx.accounts.source_vault.to_account_info();
+        let vault_data = vault_info.try_borrow_data()?;
+        if let Ok(state) = StateWithExtensions::<Token2022Account>::unpack(&vault_data) {
+            if let Ok(extension) = state.get_extension::<TransferFeeAmount>() {
+                u64::from(extension.withheld_amount) > 0
+            } else {
+                false
+            }
+        } else {
+            false
+        }
+    };
+
+    if has_fees {
+        token_2022_extensions::harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                HarvestWithheldTokensToMint {
+                    token_program_id: ctx.accounts.token_program.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                },
+            ),
+            vec![ctx.accounts.source_vault.to_account_info()],
+        )?;
+    }
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let lock_seeds = ctx.accounts.source_lock_record.signer_seeds();
+    let seeds = lock_seeds.as_seeds();
+    let signer_seeds = &[&seeds[..]];
+
+    // 1. Move the whole source vault balance into the target vault
+    let moved_amount = ctx.accounts.source_vault.amount;
+    ctx.accounts.vault.reload()?;
+    let balance_before = ctx.accounts.vault.amount;
+
+    if moved_amount > 0 {
+        transfer_hook::transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.source_vault.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.source_lock_record.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                },
+                signer_seeds,
+            )
+            .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+            moved_amount,
+            ctx.accounts.token_mint.decimals,
+        )?;
+    }
+
+    ctx.accounts.vault.reload()?;
+    let actual_amount = ctx
+        .accounts
+        .vault
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(LockerError::MathOverflow)?;
+
+    // 2. Close the emptied source vault; the source LockRecord is closed by Anchor
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.source_vault.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.source_lock_record.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    // 3. Update the target lock, keeping the later of the two unlock dates
+    let source_unlock_timestamp = ctx.accounts.source_lock_record.unlock_timestamp;
+    let lock_record = &mut ctx.accounts.lock_record;
+    lock_record.amount = lock_record
+        .amount
+        .checked_add(actual_amount)
+        .ok_or(LockerError::MathOverflow)?;
+    lock_record.unlock_timestamp = lock_record.unlock_timestamp.max(source_unlock_timestamp);
+
+    // The moved balance may differ from the source's recorded amount by stray tokens or fees
+    let source_amount = ctx.accounts.source_lock_record.amount;
+    let mint_stats = &mut ctx.accounts.mint_stats;
+    mint_stats.deposit(actual_amount.saturating_sub(source_amount))?;
+    mint_stats.release(source_amount.saturating_sub(actual_amount))?;
+    mint_stats.remove_lock()?;
+
+    emit!(LocksMerged {
+        owner: lock_record.owner,
+        mint: mint_key,
+        lock_id,
+        source_lock_id,
+        amount: actual_amount,
+        new_amount: lock_record.amount,
+        unlock_timestamp: lock_record.unlock_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(lock_id: u64, source_lock_id: u64)]
+pub struct MergeLocks<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ LockerError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    // Writable so withheld transfer fees can be harvested into it.
+    #[account(mut)]
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // The lock that survives the merge.
+    #[account(
+        mut,
+        has_one = owner,
+        has_one = vault,
+        seeds = [
+            b"lock_record",
+            lock_record.original_owner.as_ref(),
+            token_mint.key().as_ref(),
+            &lock_id.to_le_bytes()
+        ],
+        bump = lock_record.bump
+    )]
+    pub lock_record: Box<Account<'info, LockRecord>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // The lock folded into `lock_record` and closed.
+    #[account(
+        mut,
+        close = owner,
+        constraint = source_lock_record.key() != lock_record.key() @ LockerError::IncompatibleLocks,
+        constraint = source_lock_record.owner == owner.key() @ LockerError::IncompatibleLocks,
+        constraint = source_lock_record.vault == source_vault.key() @ LockerError::IncompatibleLocks,
+        seeds = [
+            b"lock_record",
+            source_lock_record.original_owner.as_ref(),
+            token_mint.key().as_ref(),
+            &source_lock_id.to_le_bytes()
+        ],
+        bump = source_lock_record.bump
+    )]
+    pub source_lock_record: Box<Account<'info, LockRecord>>,
+
+    #[account(mut)]
+    pub source_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_stats", token_mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
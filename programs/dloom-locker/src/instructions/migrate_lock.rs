@@ -0,0 +1,138 @@
+// FILE: programs/dloom_locker/src/instructions/migrate_lock.rs
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface::Mint;
+use crate::{errors::LockerError, events::LockMigrated, mint_risk::mint_risk_flags, state::{LockCounter, LockRecord, LockSchedule, MintStats}};
+
+// Lock records written before schedules, grants and ownership transfers existed.
+#[derive(AnchorDeserialize)]
+struct LegacyLockRecord {
+    bump: u8,
+    owner: Pubkey,
+    mint: Pubkey,
+    vault: Pubkey,
+    amount: u64,
+    unlock_timestamp: i64,
+    id: u64,
+}
+
+const LEGACY_LOCK_RECORD_LEN: usize = 8 + 1 + 32 + 32 + 32 + 8 + 8 + 8;
+
+pub fn handle_migrate_lock(ctx: Context<MigrateLock>, lock_id: u64) -> Result<()> {
+    let lock_info = ctx.accounts.lock_record.to_account_info();
+    require!(lock_info.data_len() == LEGACY_LOCK_RECORD_LEN, LockerError::LockAlreadyMigrated);
+
+    let legacy = {
+        let data = lock_info.try_borrow_data()?;
+        require!(data.starts_with(LockRecord::DISCRIMINATOR), LockerError::LockAlreadyMigrated);
+        LegacyLockRecord::deserialize(&mut &data[8..])?
+    };
+    require_keys_eq!(legacy.owner, ctx.accounts.owner.key(), LockerError::Unauthorized);
+    require_keys_eq!(legacy.mint, ctx.accounts.token_mint.key(), LockerError::Unauthorized);
+
+    // The legacy owner was the seed wallet, so it becomes `original_owner` and the lock keeps its address
+    let record = LockRecord {
+        bump: legacy.bump,
+        owner: legacy.owner,
+        mint: legacy.mint,
+        vault: legacy.vault,
+        amount: legacy.amount,
+        unlock_timestamp: legacy.unlock_timestamp,
+        id: legacy.id,
+        schedule: LockSchedule::Cliff,
+        withdrawn: 0,
+        funder: legacy.owner,
+        revocable: false,
+        original_owner: legacy.owner,
+        pending_owner: Pubkey::default(),
+        receipt_mint: Pubkey::default(),
+        risk_flags: mint_risk_flags(&ctx.accounts.token_mint.to_account_info())?,
+    };
+
+    // 1. Grow the account to the current layout, topping up its rent
+    let new_len = LockRecord::space(&record.schedule);
+    let top_up = Rent::get()?.minimum_balance(new_len).saturating_sub(lock_info.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: lock_info.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    lock_info.resize(new_len)?;
+    record.try_serialize(&mut &mut lock_info.try_borrow_mut_data()?[..])?;
+
+    // 2. Count the lock in the mint's stats, which did not exist when it was created
+    let mint_stats = &mut ctx.accounts.mint_stats;
+    mint_stats.bump = ctx.bumps.mint_stats;
+    mint_stats.mint = record.mint;
+    mint_stats.add_lock(record.unlock_timestamp)?;
+    mint_stats.deposit(record.amount)?;
+
+    // 3. Legacy ids were picked by clients; move the owner's counter past this one so locks
+    // funded for the owner later do not collide with it
+    ctx.accounts.lock_counter.bump = ctx.bumps.lock_counter;
+    ctx.accounts.lock_counter.reserve(record.id);
+
+    emit!(LockMigrated {
+        owner: record.owner,
+        mint: record.mint,
+        lock_id,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(lock_id: u64)]
+pub struct MigrateLock<'info> {
+    // Anyone can pay to migrate a lock.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: The wallet the legacy lock was created for; checked against the record.
+    pub owner: UncheckedAccount<'info>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: A legacy lock record. It no longer deserializes as `LockRecord`, so its layout is
+    /// checked by hand.
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [
+            b"lock_record",
+            owner.key().as_ref(),
+            token_mint.key().as_ref(),
+            &lock_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub lock_record: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + MintStats::INIT_SPACE,
+        seeds = [b"mint_stats", token_mint.key().as_ref()],
+        bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + LockCounter::INIT_SPACE,
+        seeds = [b"lock_counter", owner.key().as_ref()],
+        bump
+    )]
+    pub lock_counter: Account<'info, LockCounter>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -5,10 +5,50 @@ pub mod burn_batch;
 pub mod lock_tokens;
 pub mod withdraw_tokens;
 pub mod close_vault; 
+pub mod revoke_lock;
+pub mod increase_lock_amount;
+pub mod extend_lock;
+pub mod transfer_lock_ownership;
+pub mod accept_lock_ownership;
+pub mod split_lock;
+pub mod merge_locks;
+pub mod initialize_config;
+pub mod update_config;
+pub mod transfer_admin;
+pub mod accept_admin;
+pub mod add_fee_discount;
+pub mod remove_fee_discount;
+pub mod register_referrer;
+pub mod pause;
+pub mod unpause;
+pub mod add_mint_entry;
+pub mod remove_mint_entry;
+pub mod sync_lock;
+pub mod migrate_lock;
 
 pub use burn_from_wallet::*;
 pub use burn_batch::*;
 pub use burn_from_lock::*;
 pub use lock_tokens::*;
 pub use withdraw_tokens::*;
-pub use close_vault::*;
\ No newline at end of file
+pub use close_vault::*;
+pub use revoke_lock::*;
+pub use increase_lock_amount::*;
+pub use extend_lock::*;
+pub use transfer_lock_ownership::*;
+pub use accept_lock_ownership::*;
+pub use split_lock::*;
+pub use merge_locks::*;
+pub use initialize_config::*;
+pub use update_config::*;
+pub use transfer_admin::*;
+pub use accept_admin::*;
+pub use add_fee_discount::*;
+pub use remove_fee_discount::*;
+pub use register_referrer::*;
+pub use pause::*;
+pub use unpause::*;
+pub use add_mint_entry::*;
+pub use remove_mint_entry::*;
+pub use sync_lock::*;
+pub use migrate_lock::*;
\ No newline at end of file
@@ -0,0 +1,35 @@
+// FILE: programs/dloom_locker/src/instructions/pause.rs
+use anchor_lang::prelude::*;
+use crate::{errors::LockerError, events::ProgramPaused, state::Config};
+
+pub fn handle_pause(ctx: Context<Pause>, pause_withdrawals: bool) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let config = &mut ctx.accounts.config;
+    // Freezing withdrawals locks users out of their own funds, so only the admin may do it.
+    if pause_withdrawals {
+        require_keys_eq!(authority, config.admin, LockerError::Unauthorized);
+        config.withdrawals_paused = true;
+    }
+    config.paused = true;
+
+    emit!(ProgramPaused {
+        authority,
+        withdrawals_paused: config.withdrawals_paused,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    // The guardian or the admin.
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = authority.key() == config.guardian || authority.key() == config.admin @ LockerError::Unauthorized,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
@@ -0,0 +1,35 @@
+// FILE: programs/dloom_locker/src/instructions/register_referrer.rs
+use anchor_lang::prelude::*;
+use crate::{events::ReferrerRegistered, state::ReferralStats};
+
+pub fn handle_register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+    let referral_stats = &mut ctx.accounts.referral_stats;
+    referral_stats.bump = ctx.bumps.referral_stats;
+    referral_stats.referrer = ctx.accounts.referrer.key();
+    referral_stats.referred_locks = 0;
+    referral_stats.total_rewards_lamports = 0;
+
+    emit!(ReferrerRegistered {
+        referrer: referral_stats.referrer,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    // The wallet that will receive referral rewards; pays for its own stats account.
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = referrer,
+        space = 8 + ReferralStats::INIT_SPACE,
+        seeds = [b"referral", referrer.key().as_ref()],
+        bump
+    )]
+    pub referral_stats: Account<'info, ReferralStats>,
+
+    pub system_program: Program<'info, System>,
+}
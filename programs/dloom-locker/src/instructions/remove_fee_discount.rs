@@ -0,0 +1,31 @@
+// FILE: programs/dloom_locker/src/instructions/remove_fee_discount.rs
+use anchor_lang::prelude::*;
+use crate::{errors::LockerError, events::FeeDiscountRemoved, state::{Config, FeeDiscount}};
+
+pub fn handle_remove_fee_discount(_ctx: Context<RemoveFeeDiscount>, subject: Pubkey) -> Result<()> {
+    emit!(FeeDiscountRemoved { subject });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(subject: Pubkey)]
+pub struct RemoveFeeDiscount<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ LockerError::Unauthorized,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"fee_discount", subject.as_ref()],
+        bump = fee_discount.bump
+    )]
+    pub fee_discount: Account<'info, FeeDiscount>,
+}
@@ -0,0 +1,31 @@
+// FILE: programs/dloom_locker/src/instructions/remove_mint_entry.rs
+use anchor_lang::prelude::*;
+use crate::{errors::LockerError, events::MintEntryRemoved, state::{Config, MintEntry}};
+
+pub fn handle_remove_mint_entry(_ctx: Context<RemoveMintEntry>, mint: Pubkey) -> Result<()> {
+    emit!(MintEntryRemoved { mint });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(mint: Pubkey)]
+pub struct RemoveMintEntry<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        has_one = admin @ LockerError::Unauthorized,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = admin,
+        seeds = [b"mint_entry", mint.as_ref()],
+        bump = mint_entry.bump
+    )]
+    pub mint_entry: Account<'info, MintEntry>,
+}
@@ -0,0 +1,122 @@
+// FILE: programs/dloom_locker/src/instructions/revoke_lock.rs
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use anchor_spl::token_2022::Token2022;
+use crate::{errors::LockerError, events::LockRevoked, receipt::refresh_receipt_metadata, state::{Config, LockRecord, LockSchedule, MintStats}, transfer_hook};
+
+pub fn handle_revoke_lock<'info>(ctx: Context<'_, '_, '_, 'info, RevokeLock<'info>>, lock_id: u64) -> Result<()> {
+    require!(ctx.accounts.lock_record.revocable, LockerError::NotRevocable);
+    // Ownership can move back to the grantor; it then holds an ordinary lock of its own.
+    require_keys_neq!(ctx.accounts.lock_record.owner, ctx.accounts.funder.key(), LockerError::SelfRevocableLock);
+
+    let now = Clock::get()?.unix_timestamp;
+    let vested_amount = ctx.accounts.lock_record.withdrawable_amount(now)?;
+    let unvested_amount = ctx
+        .accounts
+        .lock_record
+        .amount
+        .checked_sub(vested_amount)
+        .ok_or(LockerError::MathOverflow)?;
+    require!(unvested_amount > 0, LockerError::NothingToRevoke);
+
+    let lock_seeds = ctx.accounts.lock_record.signer_seeds();
+    let seeds = lock_seeds.as_seeds();
+    let signer_seeds = &[&seeds[..]];
+
+    // 1. Send the unvested remainder back to the grantor
+    transfer_hook::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.funder_token_account.to_account_info(),
+                authority: ctx.accounts.lock_record.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+            },
+            signer_seeds,
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        unvested_amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    // 2. Whatever vested stays in the vault and is claimable by the beneficiary right away
+    let lock_record = &mut ctx.accounts.lock_record;
+    lock_record.amount = vested_amount;
+    lock_record.schedule = LockSchedule::Cliff;
+    lock_record.unlock_timestamp = now;
+    lock_record.revocable = false;
+    ctx.accounts.mint_stats.release(unvested_amount)?;
+    ctx.accounts.mint_stats.track_unlock(now);
+
+    refresh_receipt_metadata(
+        &ctx.accounts.lock_record,
+        ctx.accounts.receipt_mint.as_ref(),
+        ctx.accounts.receipt_token_program.as_ref(),
+    )?;
+
+    let lock_record = &ctx.accounts.lock_record;
+    emit!(LockRevoked {
+        funder: lock_record.funder,
+        owner: lock_record.owner,
+        mint: lock_record.mint,
+        lock_id,
+        returned_amount: unvested_amount,
+        vested_amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(lock_id: u64)]
+pub struct RevokeLock<'info> {
+    // The grantor that funded the lock.
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ LockerError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = funder,
+        has_one = vault,
+        seeds = [
+            b"lock_record",
+            lock_record.original_owner.as_ref(),
+            token_mint.key().as_ref(),
+            &lock_id.to_le_bytes()
+        ],
+        bump = lock_record.bump
+    )]
+    pub lock_record: Account<'info, LockRecord>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == token_mint.key()
+    )]
+    pub funder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_stats", token_mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(mut)]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+    pub receipt_token_program: Option<Program<'info, Token2022>>,
+}
@@ -0,0 +1,219 @@
+// FILE: programs/dloom_locker/src/instructions/split_lock.rs
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use anchor_spl::token_2022::Token2022;
+use crate::{errors::LockerError, events::LockSplit, receipt::refresh_receipt_metadata, state::{Config, LockCounter, LockRecord, MintStats}, transfer_hook};
+
+pub fn handle_split_lock<'info>(
+    ctx: Context<'_, '_, '_, 'info, SplitLock<'info>>,
+    lock_id: u64,
+    new_lock_id: Option<u64>,
+    amount: u64,
+    unlock_timestamp: i64,
+) -> Result<u64> {
+    ctx.accounts
+        .lock_record
+        .check_authority(
+            &ctx.accounts.owner.key(),
+            ctx.accounts.receipt_mint.as_ref(),
+            ctx.accounts.receipt_token_account.as_deref(),
+        )?;
+
+    // As with `lock_tokens`, only a split to oneself may pick the new lock's id.
+    require!(
+        new_lock_id.is_none() || ctx.accounts.new_owner.key() == ctx.accounts.owner.key(),
+        LockerError::LockIdNotAllowed
+    );
+    ctx.accounts.new_lock_counter.bump = ctx.bumps.new_lock_counter;
+    let new_lock_id = ctx.accounts.new_lock_counter.assign(new_lock_id)?;
+
+    require!(amount > 0, LockerError::ZeroAmount);
+    require!(amount <= ctx.accounts.lock_record.amount, LockerError::InsufficientFunds);
+
+    // The split-off tokens must stay locked at least as long as they were.
+    let source_unlock_timestamp = ctx.accounts.lock_record.unlock_timestamp;
+    require!(
+        unlock_timestamp >= source_unlock_timestamp,
+        LockerError::UnlockDateTooEarly
+    );
+    if unlock_timestamp > source_unlock_timestamp {
+        require!(
+            ctx.accounts.lock_record.schedule.is_extendable(),
+            LockerError::ScheduleNotExtendable
+        );
+        require!(
+            unlock_timestamp - Clock::get()?.unix_timestamp <= ctx.accounts.config.max_lock_duration,
+            LockerError::LockDurationTooLong
+        );
+    }
+
+    let mint_key = ctx.accounts.token_mint.key();
+    let lock_seeds = ctx.accounts.lock_record.signer_seeds();
+    let seeds = lock_seeds.as_seeds();
+    let signer_seeds = &[&seeds[..]];
+
+    // 1. Move the tokens vault to vault, measuring what arrives (Token-2022 transfer fees)
+    ctx.accounts.new_vault.reload()?;
+    let balance_before = ctx.accounts.new_vault.amount;
+
+    transfer_hook::transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.new_vault.to_account_info(),
+                authority: ctx.accounts.lock_record.to_account_info(),
+                mint: ctx.accounts.token_mint.to_account_info(),
+            },
+            signer_seeds,
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+        amount,
+        ctx.accounts.token_mint.decimals,
+    )?;
+
+    ctx.accounts.new_vault.reload()?;
+    let actual_amount = ctx
+        .accounts
+        .new_vault
+        .amount
+        .checked_sub(balance_before)
+        .ok_or(LockerError::MathOverflow)?;
+
+    // 2. Update the source lock. Vesting is worked out on `amount + withdrawn`, so the split-off
+    // part takes its share of what was already withdrawn; otherwise it would count as vested twice.
+    let source = &mut ctx.accounts.lock_record;
+    let moved_withdrawn = (u128::from(source.withdrawn) * u128::from(amount) / u128::from(source.amount)) as u64;
+    source.amount = source.amount.checked_sub(amount).ok_or(LockerError::MathOverflow)?;
+    source.withdrawn = source
+        .withdrawn
+        .checked_sub(moved_withdrawn)
+        .ok_or(LockerError::MathOverflow)?;
+    // Only the transfer fee charged on the move leaves the locked total
+    ctx.accounts
+        .mint_stats
+        .release(amount.checked_sub(actual_amount).ok_or(LockerError::MathOverflow)?)?;
+    ctx.accounts.mint_stats.add_lock(unlock_timestamp)?;
+    refresh_receipt_metadata(
+        &ctx.accounts.lock_record,
+        ctx.accounts.receipt_mint.as_ref(),
+        ctx.accounts.receipt_token_program.as_ref(),
+    )?;
+
+    let lock_record = &ctx.accounts.lock_record;
+
+    // 3. Initialise the new lock with the same schedule and grant terms
+    let new_lock_record = &mut ctx.accounts.new_lock_record;
+    new_lock_record.bump = ctx.bumps.new_lock_record;
+    new_lock_record.owner = ctx.accounts.new_owner.key();
+    new_lock_record.mint = mint_key;
+    new_lock_record.vault = ctx.accounts.new_vault.key();
+    new_lock_record.amount = actual_amount;
+    new_lock_record.unlock_timestamp = unlock_timestamp;
+    new_lock_record.id = new_lock_id;
+    new_lock_record.schedule = lock_record.schedule.clone();
+    new_lock_record.withdrawn = moved_withdrawn;
+    new_lock_record.funder = lock_record.funder;
+    new_lock_record.revocable = lock_record.revocable;
+    new_lock_record.original_owner = new_lock_record.owner;
+    new_lock_record.pending_owner = Pubkey::default();
+    new_lock_record.risk_flags = lock_record.risk_flags;
+
+    emit!(LockSplit {
+        owner: lock_record.owner,
+        new_owner: new_lock_record.owner,
+        mint: mint_key,
+        lock_id,
+        new_lock_id,
+        amount: actual_amount,
+        unlock_timestamp,
+    });
+
+    Ok(new_lock_id)
+}
+
+#[derive(Accounts)]
+#[instruction(lock_id: u64, new_lock_id: Option<u64>)]
+pub struct SplitLock<'info> {
+    // The lock owner, or the receipt holder for tokenized locks.
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: Any wallet can receive the split-off lock; it is only used as a PDA seed and recorded as its owner.
+    pub new_owner: UncheckedAccount<'info>,
+
+    pub token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ LockerError::ProgramPaused
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        seeds = [
+            b"lock_record",
+            lock_record.original_owner.as_ref(),
+            token_mint.key().as_ref(),
+            &lock_id.to_le_bytes()
+        ],
+        bump = lock_record.bump
+    )]
+    pub lock_record: Box<Account<'info, LockRecord>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + LockCounter::INIT_SPACE,
+        seeds = [b"lock_counter", new_owner.key().as_ref()],
+        bump
+    )]
+    pub new_lock_counter: Box<Account<'info, LockCounter>>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = LockRecord::space(&lock_record.schedule),
+        seeds = [
+            b"lock_record",
+            new_owner.key().as_ref(),
+            token_mint.key().as_ref(),
+            &new_lock_id.unwrap_or(new_lock_counter.next_id).to_le_bytes()
+        ],
+        bump
+    )]
+    pub new_lock_record: Box<Account<'info, LockRecord>>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"vault", new_lock_record.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = new_lock_record,
+        token::token_program = token_program,
+    )]
+    pub new_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_stats", token_mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Box<Account<'info, MintStats>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub rent: Sysvar<'info, Rent>,
+
+    pub receipt_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+    pub receipt_token_program: Option<Program<'info, Token2022>>,
+}
@@ -0,0 +1,190 @@
+// FILE: programs/dloom_locker/src/instructions/sync_lock.rs
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use anchor_spl::token_2022::Token2022;
+use crate::{errors::LockerError, events::LockSynced, receipt::refresh_receipt_metadata, state::{best_discount_bps, discounted_fee, Config, FeeDiscount, LockRecord, LockSchedule, MintStats}, transfer_hook};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyncMode {
+    // Adds the surplus to the lock, under the same unlock date, less the protocol's token cut.
+    // Cliff locks only, like a top-up.
+    Credit,
+    // Sends the surplus to the owner's token account right away.
+    Sweep,
+}
+
+pub fn handle_sync_lock<'info>(
+    ctx: Context<'_, '_, '_, 'info, SyncLock<'info>>,
+    lock_id: u64,
+    mode: SyncMode,
+) -> Result<()> {
+    ctx.accounts
+        .lock_record
+        .check_authority(
+            &ctx.accounts.owner.key(),
+            ctx.accounts.receipt_mint.as_ref(),
+            ctx.accounts.receipt_token_account.as_deref(),
+        )?;
+
+    // Tokens sent straight to the vault are not part of `amount` until synced
+    let surplus = ctx
+        .accounts
+        .vault
+        .amount
+        .saturating_sub(ctx.accounts.lock_record.amount);
+    require!(surplus > 0, LockerError::NothingToSync);
+
+    let lock_seeds = ctx.accounts.lock_record.signer_seeds();
+    let seeds = lock_seeds.as_seeds();
+    let signer_seeds = &[&seeds[..]];
+
+    let mut fee_amount = 0;
+    match mode {
+        SyncMode::Credit => {
+            require!(ctx.accounts.lock_record.schedule == LockSchedule::Cliff, LockerError::NotCliffLock);
+
+            // A credited surplus pays the same token cut as a top-up.
+            let discount_bps = best_discount_bps(&[
+                ctx.accounts.owner_fee_discount.as_deref(),
+                ctx.accounts.mint_fee_discount.as_deref(),
+            ]);
+            fee_amount = discounted_fee(ctx.accounts.config.lock_fee_amount(surplus)?, discount_bps);
+            if fee_amount > 0 {
+                let Some(treasury_token_account) = &ctx.accounts.treasury_token_account else {
+                    return err!(LockerError::TreasuryAccountMissing);
+                };
+                transfer_hook::transfer_checked(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        TransferChecked {
+                            from: ctx.accounts.vault.to_account_info(),
+                            to: treasury_token_account.to_account_info(),
+                            authority: ctx.accounts.lock_record.to_account_info(),
+                            mint: ctx.accounts.token_mint.to_account_info(),
+                        },
+                        signer_seeds,
+                    )
+                    .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+                    fee_amount,
+                    ctx.accounts.token_mint.decimals,
+                )?;
+            }
+            let credited = surplus.checked_sub(fee_amount).ok_or(LockerError::MathOverflow)?;
+
+            ctx.accounts.lock_record.amount = ctx
+                .accounts
+                .lock_record
+                .amount
+                .checked_add(credited)
+                .ok_or(LockerError::MathOverflow)?;
+            ctx.accounts.mint_stats.deposit(credited)?;
+
+            refresh_receipt_metadata(
+                &ctx.accounts.lock_record,
+                ctx.accounts.receipt_mint.as_ref(),
+                ctx.accounts.receipt_token_program.as_ref(),
+            )?;
+        }
+        SyncMode::Sweep => {
+            let Some(owner_token_account) = &ctx.accounts.owner_token_account else {
+                return err!(LockerError::TokenAccountMissing);
+            };
+            transfer_hook::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: owner_token_account.to_account_info(),
+                        authority: ctx.accounts.lock_record.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                    },
+                    signer_seeds,
+                )
+                .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
+                surplus,
+                ctx.accounts.token_mint.decimals,
+            )?;
+        }
+    }
+
+    let lock_record = &ctx.accounts.lock_record;
+    emit!(LockSynced {
+        owner: ctx.accounts.owner.key(),
+        mint: lock_record.mint,
+        lock_id,
+        surplus,
+        swept: mode == SyncMode::Sweep,
+        fee_amount,
+        new_amount: lock_record.amount,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(lock_id: u64)]
+pub struct SyncLock<'info> {
+    // The lock owner, or the receipt holder for tokenized locks.
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ LockerError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    // Required when the config takes a token cut: the treasury's account for this mint.
+    #[account(
+        mut,
+        constraint = treasury_token_account.mint == token_mint.key() @ LockerError::TreasuryAccountMissing,
+        constraint = treasury_token_account.owner == config.treasury @ LockerError::TreasuryAccountMissing
+    )]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // Optional: fee discount entries for the owner and for the mint.
+    #[account(seeds = [b"fee_discount", owner.key().as_ref()], bump = owner_fee_discount.bump)]
+    pub owner_fee_discount: Option<Box<Account<'info, FeeDiscount>>>,
+
+    #[account(seeds = [b"fee_discount", token_mint.key().as_ref()], bump = mint_fee_discount.bump)]
+    pub mint_fee_discount: Option<Box<Account<'info, FeeDiscount>>>,
+
+    #[account(
+        mut,
+        has_one = vault,
+        seeds = [
+            b"lock_record",
+            lock_record.original_owner.as_ref(),
+            token_mint.key().as_ref(),
+            &lock_id.to_le_bytes()
+        ],
+        bump = lock_record.bump
+    )]
+    pub lock_record: Account<'info, LockRecord>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    // Required to sweep: where the surplus is sent.
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == token_mint.key()
+    )]
+    pub owner_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_stats", token_mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub receipt_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+    pub receipt_token_program: Option<Program<'info, Token2022>>,
+}
@@ -0,0 +1,29 @@
+// FILE: programs/dloom_locker/src/instructions/transfer_admin.rs
+use anchor_lang::prelude::*;
+use crate::{errors::LockerError, events::AdminTransferStarted, state::Config};
+
+pub fn handle_transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
+    // Nothing changes until the new admin accepts. Proposing the default key cancels a pending handover.
+    let config = &mut ctx.accounts.config;
+    config.pending_admin = new_admin;
+
+    emit!(AdminTransferStarted {
+        admin: config.admin,
+        pending_admin: new_admin,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct TransferAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ LockerError::Unauthorized,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
@@ -0,0 +1,48 @@
+// FILE: programs/dloom_locker/src/instructions/transfer_lock_ownership.rs
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::{errors::LockerError, events::LockOwnershipTransferStarted, state::{Config, LockRecord}};
+
+pub fn handle_transfer_lock_ownership(ctx: Context<TransferLockOwnership>, lock_id: u64, new_owner: Pubkey) -> Result<()> {
+    // Nothing moves until the new owner accepts. Proposing the default key cancels a pending transfer.
+    let lock_record = &mut ctx.accounts.lock_record;
+    lock_record.pending_owner = new_owner;
+
+    emit!(LockOwnershipTransferStarted {
+        owner: lock_record.owner,
+        pending_owner: new_owner,
+        mint: lock_record.mint,
+        lock_id,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(lock_id: u64)]
+pub struct TransferLockOwnership<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.paused @ LockerError::ProgramPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        constraint = !lock_record.is_tokenized() @ LockerError::LockIsTokenized,
+        seeds = [
+            b"lock_record",
+            lock_record.original_owner.as_ref(),
+            token_mint.key().as_ref(),
+            &lock_id.to_le_bytes()
+        ],
+        bump = lock_record.bump
+    )]
+    pub lock_record: Account<'info, LockRecord>,
+}
@@ -0,0 +1,28 @@
+// FILE: programs/dloom_locker/src/instructions/unpause.rs
+use anchor_lang::prelude::*;
+use crate::{errors::LockerError, events::ProgramUnpaused, state::Config};
+
+pub fn handle_unpause(ctx: Context<Unpause>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.paused = false;
+    config.withdrawals_paused = false;
+
+    emit!(ProgramUnpaused {
+        admin: config.admin,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct Unpause<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ LockerError::Unauthorized,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
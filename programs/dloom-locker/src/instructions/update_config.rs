@@ -0,0 +1,28 @@
+// FILE: programs/dloom_locker/src/instructions/update_config.rs
+use anchor_lang::prelude::*;
+use crate::{errors::LockerError, events::ConfigUpdated, state::{Config, ConfigParams}};
+
+pub fn handle_update_config(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.apply(&params)?;
+
+    emit!(ConfigUpdated {
+        admin: config.admin,
+        params,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = admin @ LockerError::Unauthorized,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+}
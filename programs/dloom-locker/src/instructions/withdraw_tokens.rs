@@ -2,33 +2,92 @@ use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
 use anchor_spl::token_2022::spl_token_2022::extension::BaseStateWithExtensions;
 use anchor_spl::token_2022::spl_token_2022::{
-    extension::{transfer_fee::TransferFeeAmount, StateWithExtensions},
-    state::Account as Token2022Account,
+    extension::{
+        transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+        StateWithExtensions,
+    },
+    state::{Account as Token2022Account, Mint as Token2022Mint},
 };
-use crate::{errors::LockerError, events::TokensWithdrawn, state::LockRecord};
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_2022_extensions::{self, HarvestWithheldTokensToMint};
+use crate::{errors::LockerError, events::TokensWithdrawn, receipt::{redeem_receipt, refresh_receipt_metadata}, state::{Config, LockRecord, MintStats}, transfer_hook};
+
+pub fn handle_withdraw_tokens<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawTokens<'info>>,
+    _lock_id: u64,
+    amount: u64,
+) -> Result<()> {
+    withdraw(ctx, amount)
+}
+
+// Withdraws whatever gross amount is needed for the owner to receive `net_amount` after the
+// mint's current transfer fee.
+pub fn handle_withdraw_tokens_net<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawTokens<'info>>,
+    _lock_id: u64,
+    net_amount: u64,
+) -> Result<()> {
+    require!(net_amount > 0, LockerError::ZeroAmount);
+    let amount = gross_amount_for_net(&ctx.accounts.token_mint.to_account_info(), net_amount)?;
+    withdraw(ctx, amount)
+}
+
+// Transfer fee the mint charges on `amount` in the current epoch.
+fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    let mint_data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<Token2022Mint>::unpack(&mint_data)?;
+    let Ok(fee_config) = state.get_extension::<TransferFeeConfig>() else {
+        return Ok(0);
+    };
+    let fee = fee_config
+        .calculate_epoch_fee(Clock::get()?.epoch, amount)
+        .ok_or(LockerError::MathOverflow)?;
+    Ok(fee)
+}
+
+// Amount that has to leave the vault for `net_amount` to arrive after the current transfer fee.
+fn gross_amount_for_net(mint: &AccountInfo, net_amount: u64) -> Result<u64> {
+    let mint_data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<Token2022Mint>::unpack(&mint_data)?;
+    let Ok(fee_config) = state.get_extension::<TransferFeeConfig>() else {
+        return Ok(net_amount);
+    };
+    let fee = fee_config
+        .calculate_inverse_epoch_fee(Clock::get()?.epoch, net_amount)
+        .ok_or(LockerError::MathOverflow)?;
+    Ok(net_amount.checked_add(fee).ok_or(LockerError::MathOverflow)?)
+}
+
+fn withdraw<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawTokens<'info>>,
+    amount: u64,
+) -> Result<()> {
+    ctx.accounts
+        .lock_record
+        .check_authority(
+            &ctx.accounts.owner.key(),
+            ctx.accounts.receipt_mint.as_ref(),
+            ctx.accounts.receipt_token_account.as_deref(),
+        )?;
+
+    let withdrawable = ctx
+        .accounts
+        .lock_record
+        .withdrawable_amount(Clock::get()?.unix_timestamp)?;
+    require!(withdrawable > 0, LockerError::StillLocked);
 
-pub fn handle_withdraw_tokens(ctx: Context<WithdrawTokens>, lock_id: u64, amount: u64) -> Result<()> {
-    require!(Clock::get()?.unix_timestamp >= ctx.accounts.lock_record.unlock_timestamp, LockerError::StillLocked);
-    
     require!(amount > 0, LockerError::ZeroAmount);
-    require!(amount <= ctx.accounts.lock_record.amount, LockerError::InsufficientFunds); // Ensure you define InsufficientFunds in errors.rs
-
-    let owner_key = ctx.accounts.owner.key();
-    let mint_key = ctx.accounts.token_mint.key();
-    let bump = ctx.accounts.lock_record.bump;
-    let lock_id_bytes = lock_id.to_le_bytes(); 
-
-    let seeds = &[
-        b"lock_record".as_ref(),
-        owner_key.as_ref(),
-        mint_key.as_ref(),
-        lock_id_bytes.as_ref(),
-        &[bump],
-    ];
+    require!(amount <= ctx.accounts.lock_record.amount, LockerError::InsufficientFunds);
+    require!(amount <= withdrawable, LockerError::ExceedsVestedAmount);
+
+    let lock_seeds = ctx.accounts.lock_record.signer_seeds();
+    let seeds = lock_seeds.as_seeds();
     let signer_seeds = &[&seeds[..]];
 
-    // 1. Transfer tokens back to user
-    token_interface::transfer_checked(
+    let fee = transfer_fee(&ctx.accounts.token_mint.to_account_info(), amount)?;
+
+    // 1. Transfer tokens back to user (remaining accounts carry any transfer hook's extra accounts)
+    transfer_hook::transfer_checked(
         CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             TransferChecked {
@@ -38,14 +97,39 @@ pub fn handle_withdraw_tokens(ctx: Context<WithdrawTokens>, lock_id: u64, amount
                 mint: ctx.accounts.token_mint.to_account_info(),
             },
             signer_seeds,
-        ),
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec()),
         amount, 
         ctx.accounts.token_mint.decimals,
     )?;
 
     // CHANGE 4: Update state - subtract withdrawn amount
     ctx.accounts.lock_record.amount = ctx.accounts.lock_record.amount.checked_sub(amount).unwrap();
+    ctx.accounts.lock_record.withdrawn = ctx
+        .accounts
+        .lock_record
+        .withdrawn
+        .checked_add(amount)
+        .ok_or(LockerError::MathOverflow)?;
     let remaining_amount = ctx.accounts.lock_record.amount;
+    ctx.accounts.mint_stats.release(amount)?;
+
+    // The receipt is burned once the lock is fully withdrawn, otherwise its metadata follows the balance
+    if remaining_amount == 0 {
+        redeem_receipt(
+            &mut ctx.accounts.lock_record,
+            &ctx.accounts.owner,
+            ctx.accounts.receipt_mint.as_ref(),
+            ctx.accounts.receipt_token_account.as_ref(),
+            ctx.accounts.receipt_token_program.as_ref(),
+        )?;
+    } else {
+        refresh_receipt_metadata(
+            &ctx.accounts.lock_record,
+            ctx.accounts.receipt_mint.as_ref(),
+            ctx.accounts.receipt_token_program.as_ref(),
+        )?;
+    }
 
     // 2. Check for Transfer Fees
     let has_fees = {
@@ -63,8 +147,23 @@ pub fn handle_withdraw_tokens(ctx: Context<WithdrawTokens>, lock_id: u64, amount
     };
 
     // 3. Conditional Close Logic
-    // CHANGE 5: Only close if NO fees exist AND remaining balance is 0
-    if !has_fees && remaining_amount == 0 {
+    // Close once the lock and the vault are both empty; withheld fees are harvested to the mint
+    // first so the vault can close. Stray tokens left in the vault are recovered with sync_lock.
+    ctx.accounts.vault.reload()?;
+    if remaining_amount == 0 && ctx.accounts.vault.amount == 0 {
+        if has_fees {
+            token_2022_extensions::harvest_withheld_tokens_to_mint(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    HarvestWithheldTokensToMint {
+                        token_program_id: ctx.accounts.token_program.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                    },
+                ),
+                vec![ctx.accounts.vault.to_account_info()],
+            )?;
+        }
+
         // A. Close Vault (SPL Account)
         token_interface::close_account(CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
@@ -83,20 +182,21 @@ pub fn handle_withdraw_tokens(ctx: Context<WithdrawTokens>, lock_id: u64, amount
         let dest_starting_lamports = dest.lamports();
         **dest.try_borrow_mut_lamports()? = dest_starting_lamports.checked_add(source.lamports()).unwrap();
         **source.try_borrow_mut_lamports()? = 0;
-        
+        ctx.accounts.mint_stats.remove_lock()?;
+
         msg!("Lock fully withdrawn and accounts closed.");
+    } else if remaining_amount > 0 {
+        msg!("Partial withdrawal complete. Remaining locked: {}", remaining_amount);
     } else {
-        if remaining_amount > 0 {
-            msg!("Partial withdrawal complete. Remaining locked: {}", remaining_amount);
-        } else {
-            msg!("Vault has withheld fees. Accounts left open. User got principal tokens back.");
-        }
+        msg!("Vault still holds unaccounted tokens. Accounts left open; sync the lock to recover them.");
     }
 
     emit!(TokensWithdrawn {
         owner: ctx.accounts.owner.key(),
         mint: ctx.accounts.lock_record.mint,
-        amount: amount, 
+        amount,
+        fee,
+        net_amount: amount - fee,
     });
 
     Ok(())
@@ -105,15 +205,22 @@ pub fn handle_withdraw_tokens(ctx: Context<WithdrawTokens>, lock_id: u64, amount
 #[derive(Accounts)]
 #[instruction(lock_id: u64, amount: u64)] 
 pub struct WithdrawTokens<'info> {
+    // The lock owner, or the receipt holder for tokenized locks.
     #[account(mut)]
     pub owner: Signer<'info>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.withdrawals_paused @ LockerError::WithdrawalsPaused
+    )]
+    pub config: Account<'info, Config>,
+
     #[account(
         mut,
-        has_one = owner,
         seeds = [
             b"lock_record", 
-            owner.key().as_ref(), 
+            lock_record.original_owner.as_ref(),
             token_mint.key().as_ref(),
             &lock_id.to_le_bytes()
         ],
@@ -127,6 +234,21 @@ pub struct WithdrawTokens<'info> {
     #[account(mut)]
     pub user_token_account: InterfaceAccount<'info, TokenAccount>,
     
+    // Writable so withheld transfer fees can be harvested into it.
+    #[account(mut)]
     pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        mut,
+        seeds = [b"mint_stats", token_mint.key().as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
     pub token_program: Interface<'info, TokenInterface>,
+
+    #[account(mut)]
+    pub receipt_mint: Option<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub receipt_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub receipt_token_program: Option<Program<'info, Token2022>>,
 }
\ No newline at end of file
@@ -4,9 +4,13 @@ use anchor_lang::prelude::*;
 pub mod errors;
 pub mod events;
 pub mod instructions;
+pub mod mint_risk;
+pub mod receipt;
 pub mod state;
+pub mod transfer_hook;
 
 use instructions::*;
+use state::{ConfigParams, LockSchedule};
 
 declare_id!("AVfmdPiqXfc15Pt8PPRXxTP5oMs4D1CdijARiz8mFMFD"); 
 
@@ -14,14 +18,37 @@ declare_id!("AVfmdPiqXfc15Pt8PPRXxTP5oMs4D1CdijARiz8mFMFD");
 pub mod dloom_locker {
     use super::*;
 
-    pub fn handle_lock_tokens(ctx: Context<LockTokens>, amount: u64, unlock_timestamp: i64, lock_id: u64) -> Result<()> {
-        instructions::lock_tokens::handle_lock_tokens(ctx, amount, unlock_timestamp, lock_id)
+    // Creates a lock; without a `lock_id` the next id from the beneficiary's counter is used.
+    // Only a self-funded lock may pass its own `lock_id`.
+    // Returns the lock id.
+    pub fn handle_lock_tokens<'info>(
+        ctx: Context<'_, '_, '_, 'info, LockTokens<'info>>,
+        amount: u64,
+        unlock_timestamp: i64,
+        lock_id: Option<u64>,
+        schedule: LockSchedule,
+        revocable: bool,
+    ) -> Result<u64> {
+        instructions::lock_tokens::handle_lock_tokens(ctx, amount, unlock_timestamp, lock_id, schedule, revocable)
     }
 
-    pub fn handle_withdraw_tokens(ctx: Context<WithdrawTokens>, lock_id: u64, amount: u64,) -> Result<()> {
+    pub fn handle_withdraw_tokens<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawTokens<'info>>,
+        lock_id: u64,
+        amount: u64,
+    ) -> Result<()> {
         instructions::withdraw_tokens::handle_withdraw_tokens(ctx, lock_id, amount)
     }
 
+    // Withdraws enough to receive `net_amount` after the mint's transfer fee
+    pub fn handle_withdraw_tokens_net<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawTokens<'info>>,
+        lock_id: u64,
+        net_amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_tokens::handle_withdraw_tokens_net(ctx, lock_id, net_amount)
+    }
+
     pub fn handle_close_vault(ctx: Context<CloseVault>, lock_id: u64) -> Result<()> {
         instructions::close_vault::handle_close_vault(ctx, lock_id)
     }
@@ -43,4 +70,123 @@ pub mod dloom_locker {
     ) -> Result<()> {
         instructions::burn_batch::handle_burn_batch(ctx, amounts)
     }
-}
\ No newline at end of file
+
+    // Grantor claws back the unvested part of a revocable lock
+    pub fn handle_revoke_lock<'info>(ctx: Context<'_, '_, '_, 'info, RevokeLock<'info>>, lock_id: u64) -> Result<()> {
+        instructions::revoke_lock::handle_revoke_lock(ctx, lock_id)
+    }
+
+    // Adds more tokens to an existing lock
+    pub fn handle_increase_lock_amount<'info>(
+        ctx: Context<'_, '_, '_, 'info, IncreaseLockAmount<'info>>,
+        lock_id: u64,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::increase_lock_amount::handle_increase_lock_amount(ctx, lock_id, amount)
+    }
+
+    // Pushes the unlock date of an existing lock further out
+    pub fn handle_extend_lock(ctx: Context<ExtendLock>, lock_id: u64, new_unlock_timestamp: i64) -> Result<()> {
+        instructions::extend_lock::handle_extend_lock(ctx, lock_id, new_unlock_timestamp)
+    }
+
+    // Step 1 of an ownership transfer: the owner nominates a new owner (Pubkey::default() cancels)
+    pub fn handle_transfer_lock_ownership(ctx: Context<TransferLockOwnership>, lock_id: u64, new_owner: Pubkey) -> Result<()> {
+        instructions::transfer_lock_ownership::handle_transfer_lock_ownership(ctx, lock_id, new_owner)
+    }
+
+    // Step 2 of an ownership transfer: the nominated wallet accepts
+    pub fn handle_accept_lock_ownership(ctx: Context<AcceptLockOwnership>, lock_id: u64) -> Result<()> {
+        instructions::accept_lock_ownership::handle_accept_lock_ownership(ctx, lock_id)
+    }
+
+    // Moves part of a lock into a new lock that stays locked at least as long. Without a
+    // `new_lock_id` the new owner's counter assigns it. Returns the new lock id.
+    pub fn handle_split_lock<'info>(
+        ctx: Context<'_, '_, '_, 'info, SplitLock<'info>>,
+        lock_id: u64,
+        new_lock_id: Option<u64>,
+        amount: u64,
+        unlock_timestamp: i64,
+    ) -> Result<u64> {
+        instructions::split_lock::handle_split_lock(ctx, lock_id, new_lock_id, amount, unlock_timestamp)
+    }
+
+    // Folds one lock into another of the same mint and refunds the emptied accounts' rent
+    pub fn handle_merge_locks<'info>(
+        ctx: Context<'_, '_, '_, 'info, MergeLocks<'info>>,
+        lock_id: u64,
+        source_lock_id: u64,
+    ) -> Result<()> {
+        instructions::merge_locks::handle_merge_locks(ctx, lock_id, source_lock_id)
+    }
+
+    // One-time setup of the program config, restricted to the upgrade authority
+    pub fn handle_initialize_config(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+        instructions::initialize_config::handle_initialize_config(ctx, params)
+    }
+
+    // Admin adjusts the program tunables
+    pub fn handle_update_config(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+        instructions::update_config::handle_update_config(ctx, params)
+    }
+
+    // Step 1 of an admin handover: the admin nominates a successor (Pubkey::default() cancels)
+    pub fn handle_transfer_admin(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::transfer_admin::handle_transfer_admin(ctx, new_admin)
+    }
+
+    // Step 2 of an admin handover: the nominated wallet accepts
+    pub fn handle_accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin::handle_accept_admin(ctx)
+    }
+
+    // Admin grants a wallet or mint a discount on protocol fees (10_000 bps = exempt)
+    pub fn handle_add_fee_discount(ctx: Context<AddFeeDiscount>, subject: Pubkey, discount_bps: u16) -> Result<()> {
+        instructions::add_fee_discount::handle_add_fee_discount(ctx, subject, discount_bps)
+    }
+
+    // Admin removes a fee discount and reclaims its rent
+    pub fn handle_remove_fee_discount(ctx: Context<RemoveFeeDiscount>, subject: Pubkey) -> Result<()> {
+        instructions::remove_fee_discount::handle_remove_fee_discount(ctx, subject)
+    }
+
+    // Creates the stats account a wallet needs before it can earn referral rewards
+    pub fn handle_register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        instructions::register_referrer::handle_register_referrer(ctx)
+    }
+
+    // Emergency stop, callable by the guardian or the admin; only the admin can also pause withdrawals
+    pub fn handle_pause(ctx: Context<Pause>, pause_withdrawals: bool) -> Result<()> {
+        instructions::pause::handle_pause(ctx, pause_withdrawals)
+    }
+
+    // Admin lifts the pause, including any withdrawal pause
+    pub fn handle_unpause(ctx: Context<Unpause>) -> Result<()> {
+        instructions::unpause::handle_unpause(ctx)
+    }
+
+    // Admin allowlists (allowed = true) or denylists a mint
+    pub fn handle_add_mint_entry(ctx: Context<AddMintEntry>, mint: Pubkey, allowed: bool) -> Result<()> {
+        instructions::add_mint_entry::handle_add_mint_entry(ctx, mint, allowed)
+    }
+
+    // Admin removes a mint's allow/deny entry and reclaims its rent
+    pub fn handle_remove_mint_entry(ctx: Context<RemoveMintEntry>, mint: Pubkey) -> Result<()> {
+        instructions::remove_mint_entry::handle_remove_mint_entry(ctx, mint)
+    }
+
+    // Credits tokens sent straight to the vault to the lock, or sweeps them to the owner
+    pub fn handle_sync_lock<'info>(
+        ctx: Context<'_, '_, '_, 'info, SyncLock<'info>>,
+        lock_id: u64,
+        mode: SyncMode,
+    ) -> Result<()> {
+        instructions::sync_lock::handle_sync_lock(ctx, lock_id, mode)
+    }
+
+    // Brings a lock created before schedules and ownership transfers to the current layout; anyone can pay
+    pub fn handle_migrate_lock(ctx: Context<MigrateLock>, lock_id: u64) -> Result<()> {
+        instructions::migrate_lock::handle_migrate_lock(ctx, lock_id)
+    }
+}
@@ -0,0 +1,33 @@
+// FILE: programs/dloom_locker/src/mint_risk.rs
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::extension::BaseStateWithExtensions;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{non_transferable::NonTransferable, permanent_delegate::PermanentDelegate, StateWithExtensions},
+    state::Mint as Token2022Mint,
+};
+
+// Bits of `LockRecord::risk_flags`: mint features that let someone other than the lock
+// move, freeze or strand the vault's tokens.
+pub const RISK_PERMANENT_DELEGATE: u8 = 1 << 0;
+pub const RISK_FREEZE_AUTHORITY: u8 = 1 << 1;
+pub const RISK_NON_TRANSFERABLE: u8 = 1 << 2;
+
+// Inspects a legacy SPL or Token-2022 mint and returns its risk flags.
+pub fn mint_risk_flags(mint: &AccountInfo) -> Result<u8> {
+    let mint_data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<Token2022Mint>::unpack(&mint_data)?;
+
+    let mut flags = 0;
+    if state.base.freeze_authority.is_some() {
+        flags |= RISK_FREEZE_AUTHORITY;
+    }
+    if let Ok(extension) = state.get_extension::<PermanentDelegate>() {
+        if Option::<Pubkey>::from(extension.delegate).is_some() {
+            flags |= RISK_PERMANENT_DELEGATE;
+        }
+    }
+    if state.get_extension::<NonTransferable>().is_ok() {
+        flags |= RISK_NON_TRANSFERABLE;
+    }
+    Ok(flags)
+}
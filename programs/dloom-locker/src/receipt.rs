@@ -0,0 +1,238 @@
+// FILE: programs/dloom_locker/src/receipt.rs
+//
+// Instructions acting on an existing lock take three optional receipt accounts, all required
+// once the lock is tokenized: `receipt_token_account` proves the signer holds the receipt, and
+// `receipt_mint` with `receipt_token_program` let the receipt's metadata be refreshed or the
+// receipt be burned.
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::token_2022::{
+    self, spl_token_2022::instruction::AuthorityType, Burn, CloseAccount, MintTo, SetAuthority, Token2022,
+};
+use anchor_spl::token_2022_extensions::spl_token_metadata_interface::state::{Field, TokenMetadata};
+use anchor_spl::token_2022_extensions::{
+    token_metadata_initialize, token_metadata_update_field, TokenMetadataInitialize, TokenMetadataUpdateField,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::{errors::LockerError, state::LockRecord};
+
+pub const RECEIPT_SYMBOL: &str = "DLOCK";
+
+// Metadata fields that track the lock and are rewritten whenever it changes.
+const AMOUNT_FIELD: &str = "amount";
+const UNLOCK_TIMESTAMP_FIELD: &str = "unlock_timestamp";
+
+fn receipt_metadata(lock_record: &LockRecord, receipt_mint: Pubkey, amount: u64, unlock_timestamp: i64) -> TokenMetadata {
+    TokenMetadata {
+        mint: receipt_mint,
+        name: format!("dloom Lock #{}", lock_record.id),
+        symbol: RECEIPT_SYMBOL.to_string(),
+        additional_metadata: vec![
+            ("underlying_mint".to_string(), lock_record.mint.to_string()),
+            (AMOUNT_FIELD.to_string(), amount.to_string()),
+            (UNLOCK_TIMESTAMP_FIELD.to_string(), unlock_timestamp.to_string()),
+            ("lock_id".to_string(), lock_record.id.to_string()),
+        ],
+        ..Default::default()
+    }
+}
+
+// Writes the Token-2022 metadata for a new receipt. Must run while the lock PDA is still the
+// mint authority, i.e. before `mint_receipt`.
+pub fn initialize_receipt_metadata<'info>(
+    token_program: &Program<'info, Token2022>,
+    receipt_mint: &InterfaceAccount<'info, Mint>,
+    lock_record: &Account<'info, LockRecord>,
+    payer: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    // Fund the mint for the widest possible values up front, so later updates never need a top-up.
+    let widest = receipt_metadata(lock_record, receipt_mint.key(), u64::MAX, i64::MIN);
+    let mint_info = receipt_mint.to_account_info();
+    let required_len = mint_info
+        .data_len()
+        .checked_add(widest.tlv_size_of()?)
+        .ok_or(LockerError::MathOverflow)?;
+    let required_lamports = Rent::get()?.minimum_balance(required_len);
+    let top_up = required_lamports.saturating_sub(mint_info.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(system_program, Transfer { from: payer, to: mint_info.clone() }),
+            top_up,
+        )?;
+    }
+
+    let metadata = receipt_metadata(lock_record, receipt_mint.key(), lock_record.amount, lock_record.unlock_timestamp);
+    token_metadata_initialize(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TokenMetadataInitialize {
+                program_id: token_program.to_account_info(),
+                metadata: mint_info.clone(),
+                update_authority: lock_record.to_account_info(),
+                mint_authority: lock_record.to_account_info(),
+                mint: mint_info.clone(),
+            },
+            signer_seeds,
+        ),
+        metadata.name,
+        metadata.symbol,
+        metadata.uri,
+    )?;
+
+    for (key, value) in metadata.additional_metadata {
+        update_field(token_program, &mint_info, lock_record, Field::Key(key), value, signer_seeds)?;
+    }
+
+    Ok(())
+}
+
+// Keeps the receipt's amount and unlock date in step with the lock. Does nothing for locks
+// without a receipt.
+pub fn refresh_receipt_metadata<'info>(
+    lock_record: &Account<'info, LockRecord>,
+    receipt_mint: Option<&InterfaceAccount<'info, Mint>>,
+    token_program: Option<&Program<'info, Token2022>>,
+) -> Result<()> {
+    if !lock_record.is_tokenized() {
+        return Ok(());
+    }
+
+    let (Some(receipt_mint), Some(token_program)) = (receipt_mint, token_program) else {
+        return err!(LockerError::ReceiptAccountsMissing);
+    };
+    require_keys_eq!(receipt_mint.key(), lock_record.receipt_mint, LockerError::ReceiptAccountsMissing);
+
+    let lock_seeds = lock_record.signer_seeds();
+    let seeds = lock_seeds.as_seeds();
+    let signer_seeds = &[&seeds[..]];
+
+    let mint_info = receipt_mint.to_account_info();
+    update_field(
+        token_program,
+        &mint_info,
+        lock_record,
+        Field::Key(AMOUNT_FIELD.to_string()),
+        lock_record.amount.to_string(),
+        signer_seeds,
+    )?;
+    update_field(
+        token_program,
+        &mint_info,
+        lock_record,
+        Field::Key(UNLOCK_TIMESTAMP_FIELD.to_string()),
+        lock_record.unlock_timestamp.to_string(),
+        signer_seeds,
+    )
+}
+
+fn update_field<'info>(
+    token_program: &Program<'info, Token2022>,
+    mint_info: &AccountInfo<'info>,
+    lock_record: &Account<'info, LockRecord>,
+    field: Field,
+    value: String,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    token_metadata_update_field(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TokenMetadataUpdateField {
+                program_id: token_program.to_account_info(),
+                metadata: mint_info.clone(),
+                update_authority: lock_record.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        field,
+        value,
+    )
+}
+
+// Mints the single receipt token, then drops the mint authority so no second copy can ever exist.
+pub fn mint_receipt<'info>(
+    token_program: &Program<'info, Token2022>,
+    receipt_mint: &InterfaceAccount<'info, Mint>,
+    receipt_token_account: &InterfaceAccount<'info, TokenAccount>,
+    lock_record: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    token_2022::mint_to(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            MintTo {
+                mint: receipt_mint.to_account_info(),
+                to: receipt_token_account.to_account_info(),
+                authority: lock_record.clone(),
+            },
+            signer_seeds,
+        ),
+        1,
+    )?;
+
+    token_2022::set_authority(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            SetAuthority {
+                current_authority: lock_record,
+                account_or_mint: receipt_mint.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        AuthorityType::MintTokens,
+        None,
+    )
+}
+
+// Burns the receipt held by `holder` and turns the lock back into a plain one owned by the holder,
+// so the holder can still close it afterwards. Does nothing for locks without a receipt; a receipt
+// already burned outside this program just leaves the lock with `owner`.
+pub fn redeem_receipt<'info>(
+    lock_record: &mut LockRecord,
+    holder: &Signer<'info>,
+    receipt_mint: Option<&InterfaceAccount<'info, Mint>>,
+    receipt_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    token_program: Option<&Program<'info, Token2022>>,
+) -> Result<()> {
+    if !lock_record.is_tokenized() {
+        return Ok(());
+    }
+    if lock_record.receipt_burned(receipt_mint) {
+        lock_record.receipt_mint = Pubkey::default();
+        return Ok(());
+    }
+
+    let (Some(receipt_mint), Some(receipt_token_account), Some(token_program)) =
+        (receipt_mint, receipt_token_account, token_program)
+    else {
+        return err!(LockerError::ReceiptAccountsMissing);
+    };
+    require_keys_eq!(receipt_mint.key(), lock_record.receipt_mint, LockerError::ReceiptAccountsMissing);
+
+    token_2022::burn(
+        CpiContext::new(
+            token_program.to_account_info(),
+            Burn {
+                mint: receipt_mint.to_account_info(),
+                from: receipt_token_account.to_account_info(),
+                authority: holder.to_account_info(),
+            },
+        ),
+        1,
+    )?;
+
+    token_2022::close_account(CpiContext::new(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: receipt_token_account.to_account_info(),
+            destination: holder.to_account_info(),
+            authority: holder.to_account_info(),
+        },
+    ))?;
+
+    lock_record.owner = holder.key();
+    lock_record.receipt_mint = Pubkey::default();
+
+    Ok(())
+}
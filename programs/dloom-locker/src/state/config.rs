@@ -0,0 +1,127 @@
+// FILE: programs/dloom_locker/src/state/config.rs
+use anchor_lang::prelude::*;
+use crate::errors::LockerError;
+use super::MintEntry;
+
+// Upper bound on the token cut taken when a lock is created (10%).
+pub const MAX_LOCK_FEE_BPS: u16 = 1_000;
+
+// Singleton holding everything operators can tune without a redeploy.
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub bump: u8,
+    pub admin: Pubkey,
+    // Set while an admin handover is waiting to be accepted.
+    pub pending_admin: Pubkey,
+    // Bounds on `unlock_timestamp - now`, in seconds.
+    pub max_lock_duration: i64,
+    pub min_lock_duration: i64,
+    // Allows locks with a schedule other than `Cliff`.
+    pub vesting_enabled: bool,
+    // Allows tokenizing locks with a receipt.
+    pub receipts_enabled: bool,
+    // Wallet receiving protocol fees.
+    pub treasury: Pubkey,
+    // Flat SOL fee charged on every new lock.
+    pub lock_fee_lamports: u64,
+    // Cut of every deposit into a lock sent to the treasury, in basis points.
+    pub lock_fee_bps: u16,
+    // Share of the SOL fee and of the token cut paid to the referrer of a lock, in basis points.
+    pub referral_share_bps: u16,
+    // Can pause the program in an emergency; only the admin can unpause.
+    pub guardian: Pubkey,
+    // Stops new locks, burns and lock changes. Migrating legacy lock records is exempt: withdrawals
+    // need the current layout and stay open during a general pause.
+    pub paused: bool,
+    // Stops withdrawals and vault closes. Kept separate so users can still reach unlocked
+    // funds during a general pause; only the admin can set it.
+    pub withdrawals_paused: bool,
+    // Which mints may be locked, based on their `MintEntry`.
+    pub mint_policy: MintPolicy,
+    // Mints with any of these `mint_risk` flags are refused; other flags are only recorded.
+    pub rejected_risk_flags: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MintPolicy {
+    // Any mint can be locked.
+    Open,
+    // Only mints with an allowing entry can be locked.
+    AllowlistOnly,
+    // Any mint except those with a denying entry can be locked.
+    Denylist,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConfigParams {
+    pub max_lock_duration: i64,
+    pub min_lock_duration: i64,
+    pub vesting_enabled: bool,
+    pub receipts_enabled: bool,
+    pub treasury: Pubkey,
+    pub lock_fee_lamports: u64,
+    pub lock_fee_bps: u16,
+    pub referral_share_bps: u16,
+    pub guardian: Pubkey,
+    pub mint_policy: MintPolicy,
+    pub rejected_risk_flags: u8,
+}
+
+impl Config {
+    pub fn apply(&mut self, params: &ConfigParams) -> Result<()> {
+        require!(
+            params.min_lock_duration >= 0 && params.max_lock_duration > params.min_lock_duration,
+            LockerError::InvalidConfig
+        );
+        require!(params.treasury != Pubkey::default(), LockerError::InvalidConfig);
+        require!(params.lock_fee_bps <= MAX_LOCK_FEE_BPS, LockerError::InvalidConfig);
+        require!(params.referral_share_bps <= 10_000, LockerError::InvalidConfig);
+
+        self.max_lock_duration = params.max_lock_duration;
+        self.min_lock_duration = params.min_lock_duration;
+        self.vesting_enabled = params.vesting_enabled;
+        self.receipts_enabled = params.receipts_enabled;
+        self.treasury = params.treasury;
+        self.lock_fee_lamports = params.lock_fee_lamports;
+        self.lock_fee_bps = params.lock_fee_bps;
+        self.referral_share_bps = params.referral_share_bps;
+        self.guardian = params.guardian;
+        self.mint_policy = params.mint_policy;
+        self.rejected_risk_flags = params.rejected_risk_flags;
+        Ok(())
+    }
+
+    // Token cut taken from `amount` when a lock is created, topped up or credited.
+    pub fn lock_fee_amount(&self, amount: u64) -> Result<u64> {
+        let fee = (amount as u128)
+            .checked_mul(u128::from(self.lock_fee_bps))
+            .ok_or(LockerError::MathOverflow)?
+            / 10_000;
+        Ok(fee as u64)
+    }
+
+    // Referrer's cut of a SOL fee or token cut.
+    pub fn referral_share(&self, fee: u64) -> u64 {
+        (u128::from(fee) * u128::from(self.referral_share_bps) / 10_000) as u64
+    }
+
+    // Applies the mint policy to the mint's entry, if it has one.
+    pub fn check_mint_allowed(&self, entry: Option<&MintEntry>) -> Result<()> {
+        let allowed = match self.mint_policy {
+            MintPolicy::Open => true,
+            MintPolicy::AllowlistOnly => entry.is_some_and(|entry| entry.allowed),
+            MintPolicy::Denylist => entry.is_none_or(|entry| entry.allowed),
+        };
+        require!(allowed, LockerError::MintNotAllowed);
+        Ok(())
+    }
+
+    // Checks `unlock_timestamp` against the configured duration bounds.
+    pub fn check_lock_duration(&self, unlock_timestamp: i64, now: i64) -> Result<()> {
+        let duration = unlock_timestamp.checked_sub(now).ok_or(LockerError::MathOverflow)?;
+        require!(duration <= self.max_lock_duration, LockerError::LockDurationTooLong);
+        require!(duration >= self.min_lock_duration, LockerError::LockDurationTooShort);
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+// FILE: programs/dloom_locker/src/state/fee_discount.rs
+use anchor_lang::prelude::*;
+
+// A discount of 10_000 bps is a full exemption.
+pub const MAX_DISCOUNT_BPS: u16 = 10_000;
+
+// Admin-managed fee discount for a partner wallet or mint.
+#[account]
+#[derive(InitSpace)]
+pub struct FeeDiscount {
+    pub bump: u8,
+    // The funder wallet or token mint the discount applies to.
+    pub subject: Pubkey,
+    pub discount_bps: u16,
+}
+
+// Best discount among the entries that apply, e.g. the payer's and the mint's.
+pub fn best_discount_bps(entries: &[Option<&Account<FeeDiscount>>]) -> u16 {
+    entries
+        .iter()
+        .flatten()
+        .map(|fee_discount| fee_discount.discount_bps)
+        .max()
+        .unwrap_or(0)
+}
+
+// Part of `fee` still owed after a `discount_bps` discount.
+pub fn discounted_fee(fee: u64, discount_bps: u16) -> u64 {
+    let waived = u128::from(fee) * u128::from(discount_bps.min(MAX_DISCOUNT_BPS)) / 10_000;
+    fee - waived as u64
+}
@@ -0,0 +1,29 @@
+// FILE: programs/dloom_locker/src/state/lock_counter.rs
+use anchor_lang::prelude::*;
+use crate::errors::LockerError;
+
+// Per-beneficiary counter used to hand out lock ids when the caller does not pick one.
+#[account]
+#[derive(InitSpace)]
+pub struct LockCounter {
+    pub bump: u8,
+    // Next id to assign. Explicit ids (only the beneficiary can pick one) at or above it push it
+    // forward, so assigned ids never collide with locks created through the counter's lifetime.
+    pub next_id: u64,
+}
+
+impl LockCounter {
+    // Id for a new lock: `lock_id` when given, otherwise the next one in line.
+    pub fn assign(&mut self, lock_id: Option<u64>) -> Result<u64> {
+        let lock_id = lock_id.unwrap_or(self.next_id);
+        self.next_id = self
+            .next_id
+            .max(lock_id.checked_add(1).ok_or(LockerError::MathOverflow)?);
+        Ok(lock_id)
+    }
+
+    // Keeps the counter from handing out `lock_id`, which an existing lock already uses.
+    pub fn reserve(&mut self, lock_id: u64) {
+        self.next_id = self.next_id.max(lock_id.saturating_add(1));
+    }
+}
@@ -1,13 +1,249 @@
 // FILE: programs/dloom_locker/src/state/lock_record.rs
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use crate::errors::LockerError;
+
+pub const MAX_UNLOCK_POINTS: usize = 32;
 
 #[account]
 pub struct LockRecord {
     pub bump: u8,
+    // The beneficiary: the only wallet allowed to withdraw.
     pub owner: Pubkey,
     pub mint: Pubkey,
     pub vault: Pubkey,
     pub amount: u64,
+    // For vesting schedules this is the moment the lock is fully vested.
     pub unlock_timestamp: i64,
-    pub id: u64, 
-}
\ No newline at end of file
+    pub id: u64,
+    pub schedule: LockSchedule,
+    // Total already withdrawn by the owner, used to work out the unclaimed vested amount.
+    // A split hands the new lock its proportional share.
+    pub withdrawn: u64,
+    // Wallet that paid for and funded the lock. Equal to `owner` for self-locks.
+    pub funder: Pubkey,
+    // When set, the funder (grantor) can claw back the unvested remainder.
+    pub revocable: bool,
+    // Wallet the PDA seeds were derived from. Never changes, so the lock keeps its address
+    // when ownership moves.
+    pub original_owner: Pubkey,
+    // Set while an ownership transfer is waiting to be accepted.
+    pub pending_owner: Pubkey,
+    // Receipt mint for tokenized locks; whoever holds its single token controls the lock.
+    // `Pubkey::default()` when the lock is not tokenized.
+    pub receipt_mint: Pubkey,
+    // `mint_risk` flags observed on the mint when the lock was created.
+    pub risk_flags: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum LockSchedule {
+    // Everything unlocks at once at `unlock_timestamp`.
+    Cliff,
+    // Releases linearly from `start_timestamp` until `unlock_timestamp`.
+    Linear { start_timestamp: i64 },
+    // Releases `tranche_count` equal tranches, one every `period_seconds` from `start_timestamp`.
+    // Nothing is released before `cliff_timestamp`; the last tranche lands on `unlock_timestamp`.
+    Tranched {
+        start_timestamp: i64,
+        cliff_timestamp: i64,
+        period_seconds: i64,
+        tranche_count: u32,
+    },
+    // Releases on explicit dates. Amounts are cumulative and the last point lands on `unlock_timestamp`.
+    Custom { points: Vec<UnlockPoint> },
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UnlockPoint {
+    pub timestamp: i64,
+    pub cumulative_amount: u64,
+}
+
+impl LockSchedule {
+    // Serialized size of the schedule, so the lock record can be sized to fit it.
+    pub fn space(&self) -> usize {
+        match self {
+            LockSchedule::Cliff => 1,
+            LockSchedule::Linear { .. } => 1 + 8,
+            LockSchedule::Tranched { .. } => 1 + 8 + 8 + 8 + 4,
+            LockSchedule::Custom { points } => 1 + 4 + points.len() * (8 + 8),
+        }
+    }
+
+    // Whether `unlock_timestamp` can move later without rewriting the schedule itself.
+    pub fn is_extendable(&self) -> bool {
+        matches!(self, LockSchedule::Cliff | LockSchedule::Linear { .. })
+    }
+
+    // Start dates before the Unix epoch are refused so the vesting math cannot overflow.
+    pub fn validate(&self, amount: u64, unlock_timestamp: i64) -> Result<()> {
+        match self {
+            LockSchedule::Cliff => {}
+            LockSchedule::Linear { start_timestamp } => {
+                require!(
+                    *start_timestamp >= 0 && *start_timestamp < unlock_timestamp,
+                    LockerError::InvalidSchedule
+                );
+            }
+            LockSchedule::Tranched { start_timestamp, cliff_timestamp, period_seconds, tranche_count } => {
+                require!(*period_seconds > 0 && *tranche_count > 0, LockerError::InvalidSchedule);
+                require!(
+                    *start_timestamp >= 0 && start_timestamp <= cliff_timestamp && *cliff_timestamp <= unlock_timestamp,
+                    LockerError::InvalidSchedule
+                );
+                let end_timestamp = period_seconds
+                    .checked_mul(i64::from(*tranche_count))
+                    .and_then(|duration| start_timestamp.checked_add(duration))
+                    .ok_or(LockerError::MathOverflow)?;
+                require!(end_timestamp == unlock_timestamp, LockerError::InvalidSchedule);
+            }
+            LockSchedule::Custom { points } => {
+                require!(
+                    !points.is_empty() && points.len() <= MAX_UNLOCK_POINTS,
+                    LockerError::InvalidSchedule
+                );
+                require!(points[0].cumulative_amount > 0, LockerError::InvalidSchedule);
+                // Both dates and cumulative amounts must strictly increase.
+                for pair in points.windows(2) {
+                    require!(
+                        pair[0].timestamp < pair[1].timestamp
+                            && pair[0].cumulative_amount < pair[1].cumulative_amount,
+                        LockerError::InvalidSchedule
+                    );
+                }
+                let last = &points[points.len() - 1];
+                require!(last.cumulative_amount == amount, LockerError::InvalidSchedule);
+                require!(last.timestamp == unlock_timestamp, LockerError::InvalidSchedule);
+            }
+        }
+        Ok(())
+    }
+}
+
+// Owned copy of a lock record's PDA seeds, so the record can sign for its vault and receipt.
+pub struct LockRecordSeeds {
+    original_owner: Pubkey,
+    mint: Pubkey,
+    id: [u8; 8],
+    bump: [u8; 1],
+}
+
+impl LockRecordSeeds {
+    pub fn as_seeds(&self) -> [&[u8]; 5] {
+        [b"lock_record", self.original_owner.as_ref(), self.mint.as_ref(), &self.id, &self.bump]
+    }
+}
+
+impl LockRecord {
+    pub fn space(schedule: &LockSchedule) -> usize {
+        8 + 1 + 32 + 32 + 32 + 8 + 8 + 8 + schedule.space() + 8 + 32 + 1 + 32 + 32 + 32 + 1
+    }
+
+    pub fn signer_seeds(&self) -> LockRecordSeeds {
+        LockRecordSeeds {
+            original_owner: self.original_owner,
+            mint: self.mint,
+            id: self.id.to_le_bytes(),
+            bump: [self.bump],
+        }
+    }
+
+    pub fn is_tokenized(&self) -> bool {
+        self.receipt_mint != Pubkey::default()
+    }
+
+    // Whether the lock's receipt no longer exists because it was burned outside this program.
+    pub fn receipt_burned(&self, receipt_mint: Option<&InterfaceAccount<Mint>>) -> bool {
+        receipt_mint.is_some_and(|mint| mint.key() == self.receipt_mint && mint.supply == 0)
+    }
+
+    // Tokenized locks are controlled by the receipt holder, everything else by `owner`.
+    // Token-2022 lets a holder burn their own receipt outside this program; once the receipt
+    // mint's supply is 0 nobody holds it and the lock falls back to `owner`.
+    pub fn check_authority(
+        &self,
+        authority: &Pubkey,
+        receipt_mint: Option<&InterfaceAccount<Mint>>,
+        receipt_token_account: Option<&TokenAccount>,
+    ) -> Result<()> {
+        if !self.is_tokenized() || self.receipt_burned(receipt_mint) {
+            require_keys_eq!(*authority, self.owner, LockerError::Unauthorized);
+            return Ok(());
+        }
+        let holds_receipt = receipt_token_account.is_some_and(|account| {
+            account.mint == self.receipt_mint && account.owner == *authority && account.amount == 1
+        });
+        require!(holds_receipt, LockerError::Unauthorized);
+        Ok(())
+    }
+
+    // Amount vested at `now` out of everything ever deposited (still locked + withdrawn).
+    pub fn vested_amount(&self, now: i64) -> Result<u64> {
+        let total = self
+            .amount
+            .checked_add(self.withdrawn)
+            .ok_or(LockerError::MathOverflow)?;
+
+        if now >= self.unlock_timestamp {
+            return Ok(total);
+        }
+
+        match &self.schedule {
+            LockSchedule::Cliff => Ok(0),
+            &LockSchedule::Linear { start_timestamp } => {
+                if now <= start_timestamp {
+                    return Ok(0);
+                }
+                // Far-past start dates would overflow a plain subtraction.
+                let elapsed = now.checked_sub(start_timestamp).ok_or(LockerError::MathOverflow)? as u128;
+                let duration = self
+                    .unlock_timestamp
+                    .checked_sub(start_timestamp)
+                    .ok_or(LockerError::MathOverflow)? as u128;
+                let vested = (total as u128)
+                    .checked_mul(elapsed)
+                    .ok_or(LockerError::MathOverflow)?
+                    / duration;
+                Ok(vested as u64)
+            }
+            &LockSchedule::Tranched { start_timestamp, cliff_timestamp, period_seconds, tranche_count } => {
+                if now < cliff_timestamp || now <= start_timestamp {
+                    return Ok(0);
+                }
+                // Only whole elapsed tranches are released.
+                let elapsed_tranches = (now.checked_sub(start_timestamp).ok_or(LockerError::MathOverflow)?
+                    / period_seconds)
+                    .min(i64::from(tranche_count));
+                let vested = (total as u128)
+                    .checked_mul(elapsed_tranches as u128)
+                    .ok_or(LockerError::MathOverflow)?
+                    / u128::from(tranche_count);
+                Ok(vested as u64)
+            }
+            LockSchedule::Custom { points } => {
+                // Points are applied as a share of the final cumulative amount, so transfer fees,
+                // burns and top-ups scale the schedule instead of breaking it.
+                let reached = points
+                    .iter()
+                    .take_while(|point| point.timestamp <= now)
+                    .last()
+                    .map_or(0, |point| point.cumulative_amount);
+                let final_amount = points.last().map_or(0, |point| point.cumulative_amount);
+                if final_amount == 0 {
+                    return Ok(0);
+                }
+                let vested = (total as u128)
+                    .checked_mul(u128::from(reached))
+                    .ok_or(LockerError::MathOverflow)?
+                    / u128::from(final_amount);
+                Ok(vested as u64)
+            }
+        }
+    }
+
+    // Vested tokens the owner has not claimed yet.
+    pub fn withdrawable_amount(&self, now: i64) -> Result<u64> {
+        Ok(self.vested_amount(now)?.saturating_sub(self.withdrawn))
+    }
+}
@@ -0,0 +1,23 @@
+// FILE: programs/dloom_locker/src/state/mint_entry.rs
+use anchor_lang::prelude::*;
+
+// Admin-managed allow/deny entry for a single mint, read according to `Config::mint_policy`.
+#[account]
+#[derive(InitSpace)]
+pub struct MintEntry {
+    pub bump: u8,
+    pub mint: Pubkey,
+    // `true` allowlists the mint, `false` denylists it.
+    pub allowed: bool,
+}
+
+impl MintEntry {
+    // Reads the entry at its PDA, or `None` when no entry has been created for the mint.
+    pub fn load(account: &AccountInfo) -> Result<Option<MintEntry>> {
+        if account.owner != &crate::ID || account.data_is_empty() {
+            return Ok(None);
+        }
+        let data = account.try_borrow_data()?;
+        Ok(Some(MintEntry::try_deserialize(&mut &data[..])?))
+    }
+}
@@ -0,0 +1,51 @@
+// FILE: programs/dloom_locker/src/state/mint_stats.rs
+use anchor_lang::prelude::*;
+use crate::errors::LockerError;
+
+// Per-mint aggregate of every lock, so trackers can read one account instead of scanning
+// every LockRecord.
+#[account]
+#[derive(InitSpace)]
+pub struct MintStats {
+    pub bump: u8,
+    pub mint: Pubkey,
+    // Tokens currently held by locks of this mint.
+    pub total_locked: u64,
+    // Lock records currently open for this mint.
+    pub active_locks: u64,
+    // Everything ever deposited into locks of this mint, net of transfer fees.
+    pub all_time_locked: u64,
+    // Latest unlock date any lock of this mint has had, so no open lock unlocks after it. Closed
+    // locks still count. There is no earliest counterpart: the next unlock cannot be kept exact
+    // without scanning the open locks, so trackers read it from the LockRecords.
+    pub all_time_latest_unlock: i64,
+}
+
+impl MintStats {
+    pub fn add_lock(&mut self, unlock_timestamp: i64) -> Result<()> {
+        self.active_locks = self.active_locks.checked_add(1).ok_or(LockerError::MathOverflow)?;
+        self.track_unlock(unlock_timestamp);
+        Ok(())
+    }
+
+    pub fn remove_lock(&mut self) -> Result<()> {
+        self.active_locks = self.active_locks.checked_sub(1).ok_or(LockerError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn deposit(&mut self, amount: u64) -> Result<()> {
+        self.total_locked = self.total_locked.checked_add(amount).ok_or(LockerError::MathOverflow)?;
+        self.all_time_locked = self.all_time_locked.checked_add(amount).ok_or(LockerError::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn release(&mut self, amount: u64) -> Result<()> {
+        self.total_locked = self.total_locked.checked_sub(amount).ok_or(LockerError::MathOverflow)?;
+        Ok(())
+    }
+
+    // Called whenever a lock's unlock date is set or changed.
+    pub fn track_unlock(&mut self, unlock_timestamp: i64) {
+        self.all_time_latest_unlock = self.all_time_latest_unlock.max(unlock_timestamp);
+    }
+}
@@ -1,3 +1,15 @@
 pub mod lock_record;
+pub mod config;
+pub mod fee_discount;
+pub mod referral_stats;
+pub mod mint_entry;
+pub mod lock_counter;
+pub mod mint_stats;
 
-pub use lock_record::*;
\ No newline at end of file
+pub use lock_record::*;
+pub use config::*;
+pub use fee_discount::*;
+pub use referral_stats::*;
+pub use mint_entry::*;
+pub use lock_counter::*;
+pub use mint_stats::*;
\ No newline at end of file
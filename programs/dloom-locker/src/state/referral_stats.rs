@@ -0,0 +1,15 @@
+// FILE: programs/dloom_locker/src/state/referral_stats.rs
+use anchor_lang::prelude::*;
+
+// Running totals for a registered referrer, so payouts can be audited on-chain.
+#[account]
+#[derive(InitSpace)]
+pub struct ReferralStats {
+    pub bump: u8,
+    pub referrer: Pubkey,
+    // Locks created with this referrer attached.
+    pub referred_locks: u64,
+    // SOL paid out to the referrer from protocol fees. Token rewards span mints, so they are only
+    // reported in `TokensLocked` events.
+    pub total_rewards_lamports: u64,
+}
@@ -0,0 +1,26 @@
+// FILE: programs/dloom_locker/src/transfer_hook.rs
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::onchain::invoke_transfer_checked;
+use anchor_spl::token_interface::TransferChecked;
+
+// Drop-in for `token_interface::transfer_checked` that also works for Token-2022 mints with a
+// transfer hook. The context's remaining accounts must hold the mint's extra-account-metas PDA,
+// the accounts it lists and the hook program; mints without a hook ignore them.
+pub fn transfer_checked<'info>(
+    ctx: CpiContext<'_, '_, '_, 'info, TransferChecked<'info>>,
+    amount: u64,
+    decimals: u8,
+) -> Result<()> {
+    invoke_transfer_checked(
+        ctx.program.key,
+        ctx.accounts.from,
+        ctx.accounts.mint,
+        ctx.accounts.to,
+        ctx.accounts.authority,
+        &ctx.remaining_accounts,
+        amount,
+        decimals,
+        ctx.signer_seeds,
+    )?;
+    Ok(())
+}
@@ -1,6 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { DloomLocker } from "../target/types/dloom_locker";
+import { DummyTransferHook } from "../target/types/dummy_transfer_hook";
 import {
   createMint,
   getOrCreateAssociatedTokenAccount,
@@ -12,7 +13,16 @@ import {
   ExtensionType,
   getMintLen,
   createInitializeMintInstruction,
-  withdrawWithheldTokensFromAccounts,
+  withdrawWithheldTokensFromMint,
+  getMint,
+  getTransferFeeConfig,
+  getAssociatedTokenAddressSync,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getTokenMetadata,
+  createInitializePermanentDelegateInstruction,
+  createInitializeTransferHookInstruction,
+  transferChecked,
+  burn,
 } from "@solana/spl-token";
 import { assert } from "chai";
 
@@ -36,6 +46,26 @@ describe("dloom_locker", () => {
   const LOCK_AMOUNT = new anchor.BN(1000);
   const BURN_AMOUNT = new anchor.BN(500);
 
+  const [configPda] = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("config")],
+    program.programId
+  );
+  const treasury = anchor.web3.Keypair.generate();
+  const guardian = anchor.web3.Keypair.generate();
+  const CONFIG_PARAMS = {
+    maxLockDuration: new anchor.BN(5 * 365 * 24 * 60 * 60),
+    minLockDuration: new anchor.BN(0),
+    vestingEnabled: true,
+    receiptsEnabled: true,
+    treasury: treasury.publicKey,
+    lockFeeLamports: new anchor.BN(0),
+    lockFeeBps: 0,
+    referralShareBps: 0,
+    guardian: guardian.publicKey,
+    mintPolicy: { open: {} },
+    rejectedRiskFlags: 0,
+  };
+
   // Helper to reduce repetitive PDA code
   const findPDAs = (mint: anchor.web3.PublicKey, lockId: anchor.BN) => {
     const [lockRecordPda] = anchor.web3.PublicKey.findProgramAddressSync(
@@ -132,6 +162,25 @@ describe("dloom_locker", () => {
     console.log("Setup complete: Standard and Token22 mints created.");
   });
 
+  it("Setup: Initialize program config", async () => {
+    const [programData] = anchor.web3.PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new anchor.web3.PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+    );
+
+    await program.methods
+      .handleInitializeConfig(CONFIG_PARAMS)
+      .accountsPartial({
+        admin: wallet.publicKey,
+        programData,
+      })
+      .rpc();
+
+    const config = await program.account.config.fetch(configPda);
+    assert.ok(config.admin.equals(wallet.publicKey));
+    assert.equal(config.maxLockDuration.toNumber(), CONFIG_PARAMS.maxLockDuration.toNumber());
+  });
+
   it("Standard Token: Lock, Burn Wallet, and Withdraw", async () => {
     const lockId = new anchor.BN(1);
 
@@ -142,9 +191,10 @@ describe("dloom_locker", () => {
 
     // 1. LOCK
     await program.methods
-      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId)
+      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
       .accountsPartial({
-        owner: wallet.publicKey,
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
         tokenMint: standardMint,
         lockRecord: lockRecordPda,
         vault: vaultPda,
@@ -211,9 +261,10 @@ describe("dloom_locker", () => {
 
     // 1. Lock
     await program.methods
-      .handleLockTokens(lockAmount, unlockTime, lockId)
+      .handleLockTokens(lockAmount, unlockTime, lockId, { cliff: {} }, false)
       .accountsPartial({
-        owner: wallet.publicKey,
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
         tokenMint: standardMint,
         lockRecord: lockRecordPda,
         vault: vaultPda,
@@ -262,9 +313,10 @@ describe("dloom_locker", () => {
 
     // 1. LOCK
     await program.methods
-      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId)
+      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
       .accountsPartial({
-        owner: wallet.publicKey,
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
         tokenMint: token22Mint,
         lockRecord: lockRecordPda,
         vault: vaultPda,
@@ -316,7 +368,7 @@ describe("dloom_locker", () => {
     console.log("Token-2022 Cycle Passed!");
   });
 
-  it("Token-2022: Transfer Fees (Lock -> Burn -> Withdraw -> Harvest -> Close)", async () => {
+  it("Token-2022: Transfer Fees (Lock -> Burn -> Withdraw -> Auto-harvest & Close)", async () => {
     const mintKeypair = anchor.web3.Keypair.generate();
     feeMint = mintKeypair.publicKey;
     const decimals = 9;
@@ -394,9 +446,10 @@ describe("dloom_locker", () => {
 
     // 1. LOCK
     await program.methods
-      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId)
+      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
       .accountsPartial({
-        owner: wallet.publicKey,
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
         tokenMint: feeMint,
         lockRecord: lockRecordPda,
         vault: vaultPda,
@@ -430,9 +483,9 @@ describe("dloom_locker", () => {
     // FIX: Increased wait to 12s
     await new Promise((r) => setTimeout(r, 12000));
 
-    // This will withdraw the 900 tokens but fail to close the account because fees are stuck
+    // Withdraw part of the 900: the vault stays open and keeps its withheld fees
     await program.methods
-      .handleWithdrawTokens(lockId, LOCK_AMOUNT)
+      .handleWithdrawTokens(lockId, new anchor.BN(400))
       .accountsPartial({
         owner: wallet.publicKey,
         lockRecord: lockRecordPda,
@@ -444,30 +497,16 @@ describe("dloom_locker", () => {
       .rpc();
 
     let vaultInfo = await provider.connection.getAccountInfo(vaultPda);
-    assert.isNotNull(vaultInfo, "Vault should still exist due to stuck fees");
+    assert.isNotNull(vaultInfo, "Vault should stay open after a partial withdrawal");
 
-    // 4. HARVEST
-    console.log("Harvesting fees from Vault...");
-    await withdrawWithheldTokensFromAccounts(
-      provider.connection,
-      wallet.payer, // Payer
-      feeMint, // Mint
-      userFeeAccount, // Destination
-      wallet.payer, // Authority
-      [],
-      [vaultPda], // Harvest FROM the Vault
-      undefined,
-      TOKEN_2022_PROGRAM_ID
-    );
-
-    // 5. CLOSE VAULT
-    console.log("Closing cleaned Vault...");
+    // 4. WITHDRAW THE REST: the program harvests the withheld fees to the mint and closes everything
     await program.methods
-      .handleCloseVault(lockId)
+      .handleWithdrawTokens(lockId, new anchor.BN(500))
       .accountsPartial({
         owner: wallet.publicKey,
         lockRecord: lockRecordPda,
         vault: vaultPda,
+        userTokenAccount: userFeeAccount,
         tokenMint: feeMint,
         tokenProgram: TOKEN_2022_PROGRAM_ID,
       })
@@ -475,6 +514,29 @@ describe("dloom_locker", () => {
 
     vaultInfo = await provider.connection.getAccountInfo(vaultPda);
     assert.isNull(vaultInfo, "Vault should be fully closed now");
+    assert.isNull(
+      await provider.connection.getAccountInfo(lockRecordPda),
+      "Lock record rent should be reclaimed in the same call"
+    );
+
+    const mintAccount = await getMint(provider.connection, feeMint, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.isAbove(
+      Number(getTransferFeeConfig(mintAccount).withheldAmount),
+      0,
+      "Withheld fees should have been harvested to the mint"
+    );
+
+    // 5. The harvested fees remain claimable by the fee authority from the mint
+    await withdrawWithheldTokensFromMint(
+      provider.connection,
+      wallet.payer,
+      feeMint,
+      userFeeAccount,
+      wallet.payer,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
 
     console.log("Full Transfer Fee Cycle Passed!");
   });
@@ -488,9 +550,10 @@ describe("dloom_locker", () => {
 
     // Lock A
     await program.methods
-      .handleLockTokens(new anchor.BN(100), unlockTime, lockIdA)
+      .handleLockTokens(new anchor.BN(100), unlockTime, lockIdA, { cliff: {} }, false)
       .accountsPartial({
-        owner: wallet.publicKey,
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
         tokenMint: standardMint,
         lockRecord: pdaA.lockRecordPda,
         vault: pdaA.vaultPda,
@@ -501,9 +564,10 @@ describe("dloom_locker", () => {
 
     // Lock B
     await program.methods
-      .handleLockTokens(new anchor.BN(100), unlockTime, lockIdB)
+      .handleLockTokens(new anchor.BN(100), unlockTime, lockIdB, { cliff: {} }, false)
       .accountsPartial({
-        owner: wallet.publicKey,
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
         tokenMint: standardMint,
         lockRecord: pdaB.lockRecordPda,
         vault: pdaB.vaultPda,
@@ -514,4 +578,2548 @@ describe("dloom_locker", () => {
 
     console.log("Multiple locks created successfully.");
   });
+
+  it("Linear Vesting: Withdraw only the vested portion", async () => {
+    const lockId = new anchor.BN(20);
+    const now = Math.floor(Date.now() / 1000);
+    const startTime = new anchor.BN(now);
+    const unlockTime = new anchor.BN(now + 20);
+    const { lockRecordPda, vaultPda } = findPDAs(standardMint, lockId);
+
+    // A start date before the epoch would overflow the vesting math
+    try {
+      await program.methods
+        .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, {
+          linear: { startTimestamp: new anchor.BN("-9223372036854775808") },
+        }, false)
+        .accountsPartial({
+          funder: wallet.publicKey,
+          beneficiary: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: lockRecordPda,
+          vault: vaultPda,
+          userTokenAccount: userStandardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("A pre-epoch start date should be rejected");
+    } catch (e) {
+      assert.include(e.toString(), "InvalidSchedule");
+    }
+
+    await program.methods
+      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, {
+        linear: { startTimestamp: startTime },
+      }, false)
+      .accountsPartial({
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    console.log("Linear Vesting: Waiting 10s for part of the lock to vest...");
+    await new Promise((r) => setTimeout(r, 10000));
+
+    // Asking for everything before the end of the schedule must fail
+    try {
+      await program.methods
+        .handleWithdrawTokens(lockId, LOCK_AMOUNT)
+        .accountsPartial({
+          owner: wallet.publicKey,
+          lockRecord: lockRecordPda,
+          vault: vaultPda,
+          userTokenAccount: userStandardAccount,
+          tokenMint: standardMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Withdrawing unvested tokens should fail");
+    } catch (e) {
+      assert.include(e.toString(), "ExceedsVestedAmount");
+    }
+
+    // A small slice is already vested
+    await program.methods
+      .handleWithdrawTokens(lockId, new anchor.BN(100))
+      .accountsPartial({
+        owner: wallet.publicKey,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        tokenMint: standardMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const lockRecord = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.equal(lockRecord.amount.toNumber(), 900, "900 should remain locked");
+    assert.equal(lockRecord.withdrawn.toNumber(), 100, "100 should be withdrawn");
+
+    console.log("Linear Vesting Cycle Passed!");
+  });
+
+  it("Tranched Vesting: Cliff then whole tranches only", async () => {
+    const lockId = new anchor.BN(21);
+    const now = Math.floor(Date.now() / 1000);
+    // 3 tranches of 10s each, nothing before the first tranche (the cliff)
+    const schedule = {
+      tranched: {
+        startTimestamp: new anchor.BN(now),
+        cliffTimestamp: new anchor.BN(now + 10),
+        periodSeconds: new anchor.BN(10),
+        trancheCount: 3,
+      },
+    };
+    const unlockTime = new anchor.BN(now + 30);
+    const { lockRecordPda, vaultPda } = findPDAs(standardMint, lockId);
+
+    await program.methods
+      .handleLockTokens(new anchor.BN(900), unlockTime, lockId, schedule, false)
+      .accountsPartial({
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    console.log("Tranched Vesting: Waiting 12s for the first tranche...");
+    await new Promise((r) => setTimeout(r, 12000));
+
+    await program.methods
+      .handleWithdrawTokens(lockId, new anchor.BN(300))
+      .accountsPartial({
+        owner: wallet.publicKey,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        tokenMint: standardMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    // The second tranche is not released yet
+    try {
+      await program.methods
+        .handleWithdrawTokens(lockId, new anchor.BN(1))
+        .accountsPartial({
+          owner: wallet.publicKey,
+          lockRecord: lockRecordPda,
+          vault: vaultPda,
+          userTokenAccount: userStandardAccount,
+          tokenMint: standardMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Withdrawing before the next tranche should fail");
+    } catch (e) {
+      assert.include(e.toString(), "StillLocked");
+    }
+
+    const lockRecord = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.equal(lockRecord.amount.toNumber(), 600, "600 should remain locked");
+
+    console.log("Tranched Vesting Cycle Passed!");
+  });
+
+  it("Custom Schedule: Validate points and release on explicit dates", async () => {
+    const lockId = new anchor.BN(22);
+    const now = Math.floor(Date.now() / 1000);
+    const unlockTime = new anchor.BN(now + 1000);
+    const { lockRecordPda, vaultPda } = findPDAs(standardMint, lockId);
+    const accounts = {
+      funder: wallet.publicKey,
+      beneficiary: wallet.publicKey,
+      tokenMint: standardMint,
+      lockRecord: lockRecordPda,
+      vault: vaultPda,
+      userTokenAccount: userStandardAccount,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    // Cumulative amounts that go backwards are rejected
+    try {
+      await program.methods
+        .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, {
+          custom: {
+            points: [
+              { timestamp: new anchor.BN(now), cumulativeAmount: new anchor.BN(500) },
+              { timestamp: new anchor.BN(now + 500), cumulativeAmount: new anchor.BN(200) },
+              { timestamp: unlockTime, cumulativeAmount: LOCK_AMOUNT },
+            ],
+          },
+        }, false)
+        .accountsPartial(accounts)
+        .rpc();
+      assert.fail("Non-monotonic schedule should be rejected");
+    } catch (e) {
+      assert.include(e.toString(), "InvalidSchedule");
+    }
+
+    // 10% right away, 20% later, the rest at the unlock date
+    await program.methods
+      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, {
+        custom: {
+          points: [
+            { timestamp: new anchor.BN(now), cumulativeAmount: new anchor.BN(100) },
+            { timestamp: new anchor.BN(now + 500), cumulativeAmount: new anchor.BN(300) },
+            { timestamp: unlockTime, cumulativeAmount: LOCK_AMOUNT },
+          ],
+        },
+      }, false)
+      .accountsPartial(accounts)
+      .rpc();
+
+    await program.methods
+      .handleWithdrawTokens(lockId, new anchor.BN(100))
+      .accountsPartial({
+        owner: wallet.publicKey,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        tokenMint: standardMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const lockRecord = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.equal(lockRecord.amount.toNumber(), 900, "900 should remain locked");
+    assert.equal(lockRecord.schedule.custom.points.length, 3);
+
+    console.log("Custom Schedule Cycle Passed!");
+  });
+
+  it("Beneficiary: Treasury funds a lock only the beneficiary can withdraw", async () => {
+    const beneficiary = anchor.web3.Keypair.generate();
+    // Locks funded for someone else take the next id from the beneficiary's counter
+    const lockId = new anchor.BN(0);
+    const unlockTime = new anchor.BN(Math.floor(Date.now() / 1000) + 1000);
+
+    const [lockRecordPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("lock_record"),
+        beneficiary.publicKey.toBuffer(),
+        standardMint.toBuffer(),
+        lockId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [vaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), lockRecordPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .handleLockTokens(LOCK_AMOUNT, unlockTime, null, { cliff: {} }, false)
+      .accountsPartial({
+        funder: wallet.publicKey,
+        beneficiary: beneficiary.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const lockRecord = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.ok(lockRecord.owner.equals(beneficiary.publicKey), "Owner should be the beneficiary");
+    assert.ok(lockRecord.funder.equals(wallet.publicKey), "Funder should be the treasury wallet");
+
+    // The funder has no claim on the lock
+    try {
+      await program.methods
+        .handleWithdrawTokens(lockId, LOCK_AMOUNT)
+        .accountsPartial({
+          owner: wallet.publicKey,
+          lockRecord: lockRecordPda,
+          vault: vaultPda,
+          userTokenAccount: userStandardAccount,
+          tokenMint: standardMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Funder should not be able to withdraw");
+    } catch (e) {
+      assert.include(e.toString(), "ConstraintSeeds");
+    }
+
+    console.log("Beneficiary Lock Passed!");
+  });
+
+  it("Revocable Vesting: Grantor claws back the unvested remainder", async () => {
+    const employee = anchor.web3.Keypair.generate();
+    const lockId = new anchor.BN(0);
+    const now = Math.floor(Date.now() / 1000);
+    const unlockTime = new anchor.BN(now + 1000);
+
+    const [lockRecordPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("lock_record"),
+        employee.publicKey.toBuffer(),
+        standardMint.toBuffer(),
+        lockId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [vaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), lockRecordPda.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .handleLockTokens(
+        LOCK_AMOUNT,
+        unlockTime,
+        null,
+        { linear: { startTimestamp: new anchor.BN(now) } },
+        true
+      )
+      .accountsPartial({
+        funder: wallet.publicKey,
+        beneficiary: employee.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const balanceBefore = Number(
+      (await getAccount(provider.connection, userStandardAccount)).amount
+    );
+
+    await program.methods
+      .handleRevokeLock(lockId)
+      .accountsPartial({
+        funder: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        funderTokenAccount: userStandardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const lockRecord = await program.account.lockRecord.fetch(lockRecordPda);
+    const balanceAfter = Number(
+      (await getAccount(provider.connection, userStandardAccount)).amount
+    );
+    assert.isFalse(lockRecord.revocable, "Lock should no longer be revocable");
+    assert.equal(
+      balanceAfter - balanceBefore + lockRecord.amount.toNumber(),
+      1000,
+      "Returned and vested amounts should add up to the grant"
+    );
+    assert.isAbove(balanceAfter - balanceBefore, 900, "Most of the grant was unvested");
+
+    // A self-lock cannot be revocable, or its owner could empty it while it still looks locked
+    const selfLock = findPDAs(standardMint, new anchor.BN(90));
+    try {
+      await program.methods
+        .handleLockTokens(
+          LOCK_AMOUNT,
+          unlockTime,
+          new anchor.BN(90),
+          { linear: { startTimestamp: new anchor.BN(now) } },
+          true
+        )
+        .accountsPartial({
+          funder: wallet.publicKey,
+          beneficiary: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: selfLock.lockRecordPda,
+          vault: selfLock.vaultPda,
+          userTokenAccount: userStandardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("A revocable self-lock should be rejected");
+    } catch (e) {
+      assert.include(e.toString(), "SelfRevocableLock");
+    }
+
+    console.log("Revocable Vesting Passed!");
+  });
+
+  it("Top-up: Increase the amount of an existing lock", async () => {
+    // Lock A from the "Multiple Locks" test holds 100 tokens
+    const lockId = new anchor.BN(10);
+    const { lockRecordPda, vaultPda } = findPDAs(standardMint, lockId);
+
+    await program.methods
+      .handleIncreaseLockAmount(lockId, new anchor.BN(50))
+      .accountsPartial({
+        owner: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const lockRecord = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.equal(lockRecord.amount.toNumber(), 150, "Lock should now hold 150");
+
+    const vaultAccount = await getAccount(provider.connection, vaultPda);
+    assert.equal(Number(vaultAccount.amount), 150, "Vault should hold 150");
+
+    // A vesting lock would treat the top-up as partly vested already
+    const vestingLockId = new anchor.BN(20);
+    const vesting = findPDAs(standardMint, vestingLockId);
+    try {
+      await program.methods
+        .handleIncreaseLockAmount(vestingLockId, new anchor.BN(50))
+        .accountsPartial({
+          owner: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: vesting.lockRecordPda,
+          vault: vesting.vaultPda,
+          userTokenAccount: userStandardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Topping up a vesting lock should fail");
+    } catch (e) {
+      assert.include(e.toString(), "NotCliffLock");
+    }
+
+    console.log("Top-up Passed!");
+  });
+
+  it("Extend Lock: Unlock date only moves forward", async () => {
+    // Lock B from the "Multiple Locks" test
+    const lockId = new anchor.BN(11);
+    const { lockRecordPda } = findPDAs(standardMint, lockId);
+    const before = await program.account.lockRecord.fetch(lockRecordPda);
+    const accounts = {
+      owner: wallet.publicKey,
+      tokenMint: standardMint,
+      lockRecord: lockRecordPda,
+    };
+
+    try {
+      await program.methods
+        .handleExtendLock(lockId, before.unlockTimestamp.subn(10))
+        .accountsPartial(accounts)
+        .rpc();
+      assert.fail("Moving the unlock date earlier should fail");
+    } catch (e) {
+      assert.include(e.toString(), "UnlockDateNotLater");
+    }
+
+    const newUnlockTime = before.unlockTimestamp.addn(3600);
+    await program.methods
+      .handleExtendLock(lockId, newUnlockTime)
+      .accountsPartial(accounts)
+      .rpc();
+
+    const after = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.ok(after.unlockTimestamp.eq(newUnlockTime), "Unlock date should be extended");
+
+    console.log("Extend Lock Passed!");
+  });
+
+  it("Ownership Transfer: Propose and accept a new lock owner", async () => {
+    // The lock from the "Burn Locked Tokens" test
+    const lockId = new anchor.BN(99);
+    const newOwner = anchor.web3.Keypair.generate();
+    const { lockRecordPda, vaultPda } = findPDAs(standardMint, lockId);
+
+    await program.methods
+      .handleTransferLockOwnership(lockId, newOwner.publicKey)
+      .accountsPartial({
+        owner: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+      })
+      .rpc();
+
+    let lockRecord = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.ok(lockRecord.pendingOwner.equals(newOwner.publicKey));
+    assert.ok(lockRecord.owner.equals(wallet.publicKey), "Owner unchanged until accepted");
+
+    await program.methods
+      .handleAcceptLockOwnership(lockId)
+      .accountsPartial({
+        newOwner: newOwner.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+      })
+      .signers([newOwner])
+      .rpc();
+
+    lockRecord = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.ok(lockRecord.owner.equals(newOwner.publicKey), "Ownership should move");
+    assert.ok(lockRecord.originalOwner.equals(wallet.publicKey), "PDA seed owner is fixed");
+
+    // The previous owner can no longer act on the lock, which keeps its address
+    try {
+      await program.methods
+        .handleBurnFromLock(new anchor.BN(1), lockId)
+        .accountsPartial({
+          owner: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: lockRecordPda,
+          vault: vaultPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Previous owner should be rejected");
+    } catch (e) {
+      assert.include(e.toString(), "ConstraintHasOne");
+    }
+
+    console.log("Ownership Transfer Passed!");
+  });
+
+  it("Split Lock: Give part of a position to a partner, still locked", async () => {
+    // Lock A now holds 150 after the top-up test
+    const lockId = new anchor.BN(10);
+    // The partner's counter assigns the new lock its first id
+    const newLockId = new anchor.BN(0);
+    const partner = anchor.web3.Keypair.generate();
+    const { lockRecordPda, vaultPda } = findPDAs(standardMint, lockId);
+    const source = await program.account.lockRecord.fetch(lockRecordPda);
+
+    const [newLockRecordPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("lock_record"),
+        partner.publicKey.toBuffer(),
+        standardMint.toBuffer(),
+        newLockId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [newVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), newLockRecordPda.toBuffer()],
+      program.programId
+    );
+    const accounts = {
+      owner: wallet.publicKey,
+      newOwner: partner.publicKey,
+      tokenMint: standardMint,
+      lockRecord: lockRecordPda,
+      vault: vaultPda,
+      newLockRecord: newLockRecordPda,
+      newVault: newVaultPda,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    // The new lock cannot unlock earlier than the source
+    try {
+      await program.methods
+        .handleSplitLock(lockId, null, new anchor.BN(50), source.unlockTimestamp.subn(1))
+        .accountsPartial(accounts)
+        .rpc();
+      assert.fail("Earlier unlock should be rejected");
+    } catch (e) {
+      assert.include(e.toString(), "UnlockDateTooEarly");
+    }
+
+    await program.methods
+      .handleSplitLock(lockId, null, new anchor.BN(50), source.unlockTimestamp)
+      .accountsPartial(accounts)
+      .rpc();
+
+    const remaining = await program.account.lockRecord.fetch(lockRecordPda);
+    const split = await program.account.lockRecord.fetch(newLockRecordPda);
+    assert.equal(remaining.amount.toNumber(), 100, "Source keeps 100");
+    assert.equal(split.amount.toNumber(), 50, "New lock holds 50");
+    assert.ok(split.owner.equals(partner.publicKey), "New lock belongs to the partner");
+    assert.ok(split.unlockTimestamp.eq(source.unlockTimestamp), "Unlock date preserved");
+
+    console.log("Split Lock Passed!");
+  });
+
+  it("Split Lock: A partly withdrawn vesting lock does not release tokens early", async () => {
+    const lockId = new anchor.BN(80);
+    const newLockId = new anchor.BN(0);
+    const partner = anchor.web3.Keypair.generate();
+    const now = Math.floor(Date.now() / 1000);
+    const unlockTime = new anchor.BN(now + 100);
+    const { lockRecordPda, vaultPda } = findPDAs(standardMint, lockId);
+
+    await program.methods
+      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { linear: { startTimestamp: new anchor.BN(now) } }, false)
+      .accountsPartial({
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    // Roughly 12% vests, of which the owner takes 100
+    await new Promise((r) => setTimeout(r, 12000));
+    await program.methods
+      .handleWithdrawTokens(lockId, new anchor.BN(100))
+      .accountsPartial({
+        owner: wallet.publicKey,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        tokenMint: standardMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const [newLockRecordPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("lock_record"),
+        partner.publicKey.toBuffer(),
+        standardMint.toBuffer(),
+        newLockId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [newVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), newLockRecordPda.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .handleSplitLock(lockId, null, new anchor.BN(450), unlockTime)
+      .accountsPartial({
+        owner: wallet.publicKey,
+        newOwner: partner.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        newLockRecord: newLockRecordPda,
+        newVault: newVaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    // Half the position moves, so half of what was withdrawn moves with it
+    const source = await program.account.lockRecord.fetch(lockRecordPda);
+    const split = await program.account.lockRecord.fetch(newLockRecordPda);
+    assert.equal(source.amount.toNumber(), 450);
+    assert.equal(source.withdrawn.toNumber(), 50);
+    assert.equal(split.amount.toNumber(), 450);
+    assert.equal(split.withdrawn.toNumber(), 50);
+
+    // Without that share the partner could take ~12% of 450 straight away
+    const partnerAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        wallet.payer,
+        standardMint,
+        partner.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_PROGRAM_ID
+      )
+    ).address;
+    try {
+      await program.methods
+        .handleWithdrawTokens(newLockId, new anchor.BN(40))
+        .accountsPartial({
+          owner: partner.publicKey,
+          lockRecord: newLockRecordPda,
+          vault: newVaultPda,
+          userTokenAccount: partnerAccount,
+          tokenMint: standardMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([partner])
+        .rpc();
+      assert.fail("The partner should not be able to withdraw ahead of the schedule");
+    } catch (e) {
+      assert.match(e.toString(), /ExceedsVestedAmount|StillLocked/);
+    }
+  });
+
+  it("Merge Locks: Fold one lock into another and close it", async () => {
+    const targetId = new anchor.BN(50);
+    const sourceId = new anchor.BN(51);
+    const now = Math.floor(Date.now() / 1000);
+    const target = findPDAs(standardMint, targetId);
+    const source = findPDAs(standardMint, sourceId);
+
+    for (const [lockId, pdas, unlock] of [
+      [targetId, target, now + 1000],
+      [sourceId, source, now + 2000],
+    ] as const) {
+      await program.methods
+        .handleLockTokens(new anchor.BN(100), new anchor.BN(unlock), lockId, { cliff: {} }, false)
+        .accountsPartial({
+          funder: wallet.publicKey,
+          beneficiary: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: pdas.lockRecordPda,
+          vault: pdas.vaultPda,
+          userTokenAccount: userStandardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    }
+
+    await program.methods
+      .handleMergeLocks(targetId, sourceId)
+      .accountsPartial({
+        owner: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: target.lockRecordPda,
+        vault: target.vaultPda,
+        sourceLockRecord: source.lockRecordPda,
+        sourceVault: source.vaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const merged = await program.account.lockRecord.fetch(target.lockRecordPda);
+    assert.equal(merged.amount.toNumber(), 200, "Target should hold both positions");
+    assert.equal(merged.unlockTimestamp.toNumber(), now + 2000, "Later unlock date wins");
+
+    assert.isNull(
+      await provider.connection.getAccountInfo(source.lockRecordPda),
+      "Source LockRecord should be closed"
+    );
+    assert.isNull(
+      await provider.connection.getAccountInfo(source.vaultPda),
+      "Source vault should be closed"
+    );
+
+    console.log("Merge Locks Passed!");
+  });
+
+  it("Lock Receipts: Tokenized lock is controlled by the receipt holder", async () => {
+    const lockId = new anchor.BN(60);
+    const unlockTime = new anchor.BN(Math.floor(Date.now() / 1000) + 1000);
+    const { lockRecordPda, vaultPda } = findPDAs(standardMint, lockId);
+    const [receiptMintPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), lockRecordPda.toBuffer()],
+      program.programId
+    );
+    const receiptTokenAccount = getAssociatedTokenAddressSync(
+      receiptMintPda,
+      wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await program.methods
+      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
+      .accountsPartial({
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        receiptMint: receiptMintPda,
+        receiptTokenAccount,
+        receiptTokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const lockRecord = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.ok(lockRecord.receiptMint.equals(receiptMintPda), "Receipt mint recorded");
+    const receipt = await getAccount(
+      provider.connection,
+      receiptTokenAccount,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(Number(receipt.amount), 1, "Exactly one receipt minted");
+
+    const metadata = await getTokenMetadata(
+      provider.connection,
+      receiptMintPda,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const fields = Object.fromEntries(metadata.additionalMetadata);
+    assert.equal(metadata.name, "dloom Lock #60");
+    assert.equal(fields["underlying_mint"], standardMint.toBase58());
+    assert.equal(fields["amount"], "1000");
+    assert.equal(fields["lock_id"], "60");
+
+    // Without presenting the receipt, even the original owner is refused
+    try {
+      await program.methods
+        .handleExtendLock(lockId, unlockTime.addn(60))
+        .accountsPartial({
+          owner: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: lockRecordPda,
+          receiptTokenAccount: null,
+        })
+        .rpc();
+      assert.fail("Extending without the receipt should fail");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+
+    await program.methods
+      .handleExtendLock(lockId, unlockTime.addn(60))
+      .accountsPartial({
+        owner: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        receiptTokenAccount,
+        receiptMint: receiptMintPda,
+        receiptTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    // The receipt metadata follows the new unlock date
+    const updated = await getTokenMetadata(
+      provider.connection,
+      receiptMintPda,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(
+      Object.fromEntries(updated.additionalMetadata)["unlock_timestamp"],
+      unlockTime.addn(60).toString()
+    );
+
+    console.log("Lock Receipts Passed!");
+  });
+
+  it("Lock Receipts: Selling the receipt hands over the lock", async () => {
+    const lockId = new anchor.BN(61);
+    const unlockTime = new anchor.BN(Math.floor(Date.now() / 1000) + 10);
+    const buyer = anchor.web3.Keypair.generate();
+    const { lockRecordPda, vaultPda } = findPDAs(standardMint, lockId);
+    const [receiptMintPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), lockRecordPda.toBuffer()],
+      program.programId
+    );
+    const sellerReceipt = getAssociatedTokenAddressSync(
+      receiptMintPda,
+      wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await program.methods
+      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
+      .accountsPartial({
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        receiptMint: receiptMintPda,
+        receiptTokenAccount: sellerReceipt,
+        receiptTokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    // The receipt changes hands on an ordinary Token-2022 transfer
+    const buyerReceipt = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        wallet.payer,
+        receiptMintPda,
+        buyer.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).address;
+    const buyerTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        wallet.payer,
+        standardMint,
+        buyer.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_PROGRAM_ID
+      )
+    ).address;
+    await transferChecked(
+      provider.connection,
+      wallet.payer,
+      sellerReceipt,
+      receiptMintPda,
+      buyerReceipt,
+      wallet.payer,
+      1,
+      0,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const receiptAccounts = {
+      tokenMint: standardMint,
+      lockRecord: lockRecordPda,
+      vault: vaultPda,
+      receiptMint: receiptMintPda,
+      receiptTokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+
+    // The seller no longer controls the lock
+    try {
+      await program.methods
+        .handleBurnFromLock(new anchor.BN(100), lockId)
+        .accountsPartial({ ...receiptAccounts, owner: wallet.publicKey, receiptTokenAccount: sellerReceipt })
+        .rpc();
+      assert.fail("The seller should no longer control the lock");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+
+    // The buyer does, and the receipt metadata follows the balance
+    await program.methods
+      .handleBurnFromLock(new anchor.BN(100), lockId)
+      .accountsPartial({ ...receiptAccounts, owner: buyer.publicKey, receiptTokenAccount: buyerReceipt })
+      .signers([buyer])
+      .rpc();
+    const metadata = await getTokenMetadata(
+      provider.connection,
+      receiptMintPda,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(Object.fromEntries(metadata.additionalMetadata)["amount"], "900");
+
+    await new Promise((r) => setTimeout(r, 12000));
+
+    try {
+      await program.methods
+        .handleWithdrawTokens(lockId, new anchor.BN(900))
+        .accountsPartial({
+          ...receiptAccounts,
+          owner: wallet.publicKey,
+          userTokenAccount: userStandardAccount,
+          receiptTokenAccount: sellerReceipt,
+        })
+        .rpc();
+      assert.fail("The seller should not be able to withdraw");
+    } catch (e) {
+      assert.include(e.toString(), "Unauthorized");
+    }
+
+    // A full withdrawal by the buyer burns the receipt and closes the lock
+    await program.methods
+      .handleWithdrawTokens(lockId, new anchor.BN(900))
+      .accountsPartial({
+        ...receiptAccounts,
+        owner: buyer.publicKey,
+        userTokenAccount: buyerTokenAccount,
+        receiptTokenAccount: buyerReceipt,
+      })
+      .signers([buyer])
+      .rpc();
+
+    const buyerBalance = await getAccount(provider.connection, buyerTokenAccount, undefined, TOKEN_PROGRAM_ID);
+    assert.equal(Number(buyerBalance.amount), 900);
+    const receiptMint = await getMint(provider.connection, receiptMintPda, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.equal(Number(receiptMint.supply), 0, "The receipt is burned");
+    assert.isNull(await provider.connection.getAccountInfo(buyerReceipt), "The receipt account is closed");
+    assert.isNull(await program.account.lockRecord.fetchNullable(lockRecordPda), "The lock is closed");
+  });
+
+  it("Lock Receipts: Revocable grants cannot be tokenized, burned receipts fall back to the owner", async () => {
+    const unlockTime = new anchor.BN(Math.floor(Date.now() / 1000) + 5);
+
+    // A buyer of the receipt could not tell that the grantor keeps the right to revoke
+    const employee = anchor.web3.Keypair.generate();
+    const grantId = new anchor.BN(0);
+    const [grantRecordPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("lock_record"),
+        employee.publicKey.toBuffer(),
+        standardMint.toBuffer(),
+        grantId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [grantVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), grantRecordPda.toBuffer()],
+      program.programId
+    );
+    const [grantReceiptPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), grantRecordPda.toBuffer()],
+      program.programId
+    );
+    try {
+      await program.methods
+        .handleLockTokens(LOCK_AMOUNT, unlockTime, null, { cliff: {} }, true)
+        .accountsPartial({
+          funder: wallet.publicKey,
+          beneficiary: employee.publicKey,
+          tokenMint: standardMint,
+          lockRecord: grantRecordPda,
+          vault: grantVaultPda,
+          userTokenAccount: userStandardAccount,
+          receiptMint: grantReceiptPda,
+          receiptTokenAccount: getAssociatedTokenAddressSync(
+            grantReceiptPda,
+            employee.publicKey,
+            false,
+            TOKEN_2022_PROGRAM_ID
+          ),
+          receiptTokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("A revocable lock should not get a receipt");
+    } catch (e) {
+      assert.include(e.toString(), "RevocableReceipt");
+    }
+
+    // A receipt burned straight through Token-2022 hands the lock back to its owner
+    const lockId = new anchor.BN(62);
+    const { lockRecordPda, vaultPda } = findPDAs(standardMint, lockId);
+    const [receiptMintPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("receipt"), lockRecordPda.toBuffer()],
+      program.programId
+    );
+    const receiptTokenAccount = getAssociatedTokenAddressSync(
+      receiptMintPda,
+      wallet.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await program.methods
+      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
+      .accountsPartial({
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        receiptMint: receiptMintPda,
+        receiptTokenAccount,
+        receiptTokenProgram: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    await burn(
+      provider.connection,
+      wallet.payer,
+      receiptTokenAccount,
+      receiptMintPda,
+      wallet.payer,
+      1,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await new Promise((r) => setTimeout(r, 6000));
+
+    const balanceBefore = Number((await getAccount(provider.connection, userStandardAccount)).amount);
+    await program.methods
+      .handleWithdrawTokens(lockId, LOCK_AMOUNT)
+      .accountsPartial({
+        owner: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        receiptMint: receiptMintPda,
+        receiptTokenAccount: null,
+        receiptTokenProgram: TOKEN_2022_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    const balanceAfter = Number((await getAccount(provider.connection, userStandardAccount)).amount);
+    assert.equal(balanceAfter - balanceBefore, 1000, "The owner recovers the tokens");
+    assert.isNull(await program.account.lockRecord.fetchNullable(lockRecordPda), "The lock is closed");
+  });
+
+  it("Config: Admin updates tunables and hands over the role", async () => {
+    // Invalid bounds are rejected
+    try {
+      await program.methods
+        .handleUpdateConfig({
+          ...CONFIG_PARAMS,
+          minLockDuration: CONFIG_PARAMS.maxLockDuration,
+        })
+        .accountsPartial({ admin: wallet.publicKey })
+        .rpc();
+      assert.fail("Should have rejected min >= max");
+    } catch (e) {
+      assert.include(e.message, "InvalidConfig");
+    }
+
+    // With a one-hour minimum, a lock shorter than that is rejected
+    await program.methods
+      .handleUpdateConfig({ ...CONFIG_PARAMS, minLockDuration: new anchor.BN(3600) })
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+
+    const lockId = new anchor.BN(70);
+    try {
+      await program.methods
+        .handleLockTokens(
+          LOCK_AMOUNT,
+          new anchor.BN(Math.floor(Date.now() / 1000) + 60),
+          lockId,
+          { cliff: {} },
+          false
+        )
+        .accountsPartial({
+          funder: wallet.publicKey,
+          beneficiary: wallet.publicKey,
+          tokenMint: standardMint,
+          userTokenAccount: userStandardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Should have rejected a lock below the minimum duration");
+    } catch (e) {
+      assert.include(e.message, "LockDurationTooShort");
+    }
+
+    // Two-step handover to a new admin, who can then update the config
+    const newAdmin = anchor.web3.Keypair.generate();
+    await program.methods
+      .handleTransferAdmin(newAdmin.publicKey)
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+
+    try {
+      await program.methods
+        .handleAcceptAdmin()
+        .accountsPartial({ newAdmin: wallet.publicKey })
+        .rpc();
+      assert.fail("Only the nominated wallet can accept");
+    } catch (e) {
+      assert.include(e.message, "NotPendingAdmin");
+    }
+
+    await program.methods
+      .handleAcceptAdmin()
+      .accountsPartial({ newAdmin: newAdmin.publicKey })
+      .signers([newAdmin])
+      .rpc();
+
+    try {
+      await program.methods
+        .handleUpdateConfig(CONFIG_PARAMS)
+        .accountsPartial({ admin: wallet.publicKey })
+        .rpc();
+      assert.fail("The previous admin should have lost access");
+    } catch (e) {
+      assert.include(e.message, "Unauthorized");
+    }
+
+    await program.methods
+      .handleUpdateConfig(CONFIG_PARAMS)
+      .accountsPartial({ admin: newAdmin.publicKey })
+      .signers([newAdmin])
+      .rpc();
+
+    // Hand the role back so the remaining tests can keep using the provider wallet
+    await program.methods
+      .handleTransferAdmin(wallet.publicKey)
+      .accountsPartial({ admin: newAdmin.publicKey })
+      .signers([newAdmin])
+      .rpc();
+    await program.methods
+      .handleAcceptAdmin()
+      .accountsPartial({ newAdmin: wallet.publicKey })
+      .rpc();
+
+    const config = await program.account.config.fetch(configPda);
+    assert.ok(config.admin.equals(wallet.publicKey));
+    assert.equal(config.minLockDuration.toNumber(), 0);
+  });
+
+  it("Protocol Fee: SOL fee and token cut go to the treasury", async () => {
+    const lockId = new anchor.BN(71);
+    const { lockRecordPda, vaultPda } = findPDAs(standardMint, lockId);
+    const feeLamports = 1_000_000;
+
+    await program.methods
+      .handleUpdateConfig({
+        ...CONFIG_PARAMS,
+        lockFeeLamports: new anchor.BN(feeLamports),
+        lockFeeBps: 100,
+      })
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+
+    const treasuryTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        wallet.payer,
+        standardMint,
+        treasury.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_PROGRAM_ID
+      )
+    ).address;
+    const unlockTime = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+
+    // Without the treasury's token account the token cut cannot be paid
+    try {
+      await program.methods
+        .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
+        .accountsPartial({
+          funder: wallet.publicKey,
+          beneficiary: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: lockRecordPda,
+          vault: vaultPda,
+          userTokenAccount: userStandardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Should have required the treasury token account");
+    } catch (e) {
+      assert.include(e.message, "TreasuryAccountMissing");
+    }
+
+    const treasuryLamportsBefore = await provider.connection.getBalance(treasury.publicKey);
+
+    await program.methods
+      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
+      .accountsPartial({
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        treasuryTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const treasuryLamportsAfter = await provider.connection.getBalance(treasury.publicKey);
+    assert.equal(treasuryLamportsAfter - treasuryLamportsBefore, feeLamports);
+
+    // 1% of 1000 goes to the treasury, the rest is locked
+    const treasuryTokens = await getAccount(provider.connection, treasuryTokenAccount, undefined, TOKEN_PROGRAM_ID);
+    assert.equal(Number(treasuryTokens.amount), 10);
+    let record = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.equal(record.amount.toNumber(), 990);
+
+    // Topping up pays the same cut, so a small lock cannot be grown fee-free
+    await program.methods
+      .handleIncreaseLockAmount(lockId, LOCK_AMOUNT)
+      .accountsPartial({
+        owner: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        treasuryTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    record = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.equal(record.amount.toNumber(), 1980);
+
+    // So do tokens sent straight to the vault and credited to the lock
+    await transferChecked(
+      provider.connection,
+      wallet.payer,
+      userStandardAccount,
+      standardMint,
+      vaultPda,
+      wallet.payer,
+      500,
+      9,
+      [],
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    await program.methods
+      .handleSyncLock(lockId, { credit: {} } as any)
+      .accountsPartial({
+        owner: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        ownerTokenAccount: null,
+        treasuryTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    record = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.equal(record.amount.toNumber(), 2475);
+    const treasuryTotal = await getAccount(provider.connection, treasuryTokenAccount, undefined, TOKEN_PROGRAM_ID);
+    assert.equal(Number(treasuryTotal.amount), 25, "10 + 10 + 5 went to the treasury");
+
+    // Fees above the cap are rejected
+    try {
+      await program.methods
+        .handleUpdateConfig({ ...CONFIG_PARAMS, lockFeeBps: 1001 })
+        .accountsPartial({ admin: wallet.publicKey })
+        .rpc();
+      assert.fail("Should have rejected a fee above the cap");
+    } catch (e) {
+      assert.include(e.message, "InvalidConfig");
+    }
+
+    await program.methods
+      .handleUpdateConfig(CONFIG_PARAMS)
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+  });
+
+  it("Fee Discounts: Partners lock at a discount or for free", async () => {
+    const feeLamports = 1_000_000;
+    await program.methods
+      .handleUpdateConfig({
+        ...CONFIG_PARAMS,
+        lockFeeLamports: new anchor.BN(feeLamports),
+        lockFeeBps: 100,
+      })
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+
+    const [mintDiscountPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("fee_discount"), standardMint.toBuffer()],
+      program.programId
+    );
+    const [walletDiscountPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("fee_discount"), wallet.publicKey.toBuffer()],
+      program.programId
+    );
+    const treasuryTokenAccount = getAssociatedTokenAddressSync(standardMint, treasury.publicKey);
+    const unlockTime = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+
+    const lock = async (lockId: anchor.BN, discounts: object) => {
+      const { lockRecordPda, vaultPda } = findPDAs(standardMint, lockId);
+      await program.methods
+        .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
+        .accountsPartial({
+          funder: wallet.publicKey,
+          beneficiary: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: lockRecordPda,
+          vault: vaultPda,
+          userTokenAccount: userStandardAccount,
+          treasuryTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          ...discounts,
+        })
+        .rpc();
+      return program.account.lockRecord.fetch(lockRecordPda);
+    };
+
+    // Only the admin manages the registry
+    const stranger = anchor.web3.Keypair.generate();
+    try {
+      await program.methods
+        .handleAddFeeDiscount(standardMint, 5000)
+        .accountsPartial({ admin: stranger.publicKey })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Only the admin can add discounts");
+    } catch (e) {
+      assert.ok(e);
+    }
+
+    // Half off for the mint: 5 of the 10 token fee and half the SOL fee
+    await program.methods
+      .handleAddFeeDiscount(standardMint, 5000)
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+
+    let treasuryLamports = await provider.connection.getBalance(treasury.publicKey);
+    let record = await lock(new anchor.BN(72), { mintFeeDiscount: mintDiscountPda });
+    assert.equal(record.amount.toNumber(), 995);
+    assert.equal(
+      (await provider.connection.getBalance(treasury.publicKey)) - treasuryLamports,
+      feeLamports / 2
+    );
+
+    // A full exemption for the wallet wins over the smaller mint discount
+    await program.methods
+      .handleAddFeeDiscount(wallet.publicKey, 10000)
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+
+    treasuryLamports = await provider.connection.getBalance(treasury.publicKey);
+    record = await lock(new anchor.BN(73), {
+      mintFeeDiscount: mintDiscountPda,
+      funderFeeDiscount: walletDiscountPda,
+    });
+    assert.equal(record.amount.toNumber(), 1000);
+    assert.equal(await provider.connection.getBalance(treasury.publicKey), treasuryLamports);
+
+    // Removing the entries closes them
+    for (const subject of [standardMint, wallet.publicKey]) {
+      await program.methods
+        .handleRemoveFeeDiscount(subject)
+        .accountsPartial({ admin: wallet.publicKey })
+        .rpc();
+    }
+    assert.isNull(await provider.connection.getAccountInfo(mintDiscountPda));
+    assert.isNull(await provider.connection.getAccountInfo(walletDiscountPda));
+
+    await program.methods
+      .handleUpdateConfig(CONFIG_PARAMS)
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+  });
+
+  it("Referrals: A registered referrer earns a share of the SOL fee and the token cut", async () => {
+    const feeLamports = 1_000_000;
+    await program.methods
+      .handleUpdateConfig({
+        ...CONFIG_PARAMS,
+        lockFeeLamports: new anchor.BN(feeLamports),
+        lockFeeBps: 100,
+        referralShareBps: 2000,
+      })
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+
+    // The launchpad pays for its own stats account
+    const launchpad = anchor.web3.Keypair.generate();
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: wallet.publicKey,
+          toPubkey: launchpad.publicKey,
+          lamports: anchor.web3.LAMPORTS_PER_SOL / 10,
+        })
+      )
+    );
+    const [referralStatsPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("referral"), launchpad.publicKey.toBuffer()],
+      program.programId
+    );
+    await program.methods
+      .handleRegisterReferrer()
+      .accountsPartial({ referrer: launchpad.publicKey })
+      .signers([launchpad])
+      .rpc();
+
+    const lockId = new anchor.BN(74);
+    const { lockRecordPda, vaultPda } = findPDAs(standardMint, lockId);
+    const unlockTime = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+    const treasuryTokenAccount = getAssociatedTokenAddressSync(standardMint, treasury.publicKey);
+    const launchpadTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        wallet.payer,
+        standardMint,
+        launchpad.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_PROGRAM_ID
+      )
+    ).address;
+    const treasuryBefore = await provider.connection.getBalance(treasury.publicKey);
+    const launchpadBefore = await provider.connection.getBalance(launchpad.publicKey);
+    const treasuryTokensBefore = Number(
+      (await getAccount(provider.connection, treasuryTokenAccount, undefined, TOKEN_PROGRAM_ID)).amount
+    );
+
+    // The referrer's token account is needed to pay its share of the token cut
+    try {
+      await program.methods
+        .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
+        .accountsPartial({
+          funder: wallet.publicKey,
+          beneficiary: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: lockRecordPda,
+          vault: vaultPda,
+          userTokenAccount: userStandardAccount,
+          treasuryTokenAccount,
+          referrer: launchpad.publicKey,
+          referralStats: referralStatsPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Should have required the referrer's token account");
+    } catch (e) {
+      assert.include(e.message, "InvalidReferrer");
+    }
+
+    await program.methods
+      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
+      .accountsPartial({
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        treasuryTokenAccount,
+        referrer: launchpad.publicKey,
+        referrerTokenAccount: launchpadTokenAccount,
+        referralStats: referralStatsPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    // 20% of both fees goes to the launchpad, the rest to the treasury
+    assert.equal(
+      (await provider.connection.getBalance(launchpad.publicKey)) - launchpadBefore,
+      feeLamports / 5
+    );
+    assert.equal(
+      (await provider.connection.getBalance(treasury.publicKey)) - treasuryBefore,
+      (feeLamports * 4) / 5
+    );
+    const stats = await program.account.referralStats.fetch(referralStatsPda);
+    assert.equal(stats.referredLocks.toNumber(), 1);
+    assert.equal(stats.totalRewardsLamports.toNumber(), feeLamports / 5);
+    const launchpadTokens = await getAccount(provider.connection, launchpadTokenAccount, undefined, TOKEN_PROGRAM_ID);
+    assert.equal(Number(launchpadTokens.amount), 2, "20% of the 10 token cut");
+    const treasuryTokens = await getAccount(provider.connection, treasuryTokenAccount, undefined, TOKEN_PROGRAM_ID);
+    assert.equal(Number(treasuryTokens.amount) - treasuryTokensBefore, 8);
+
+    // A referrer that does not match its stats account is refused
+    try {
+      const other = findPDAs(standardMint, new anchor.BN(75));
+      await program.methods
+        .handleLockTokens(LOCK_AMOUNT, unlockTime, new anchor.BN(75), { cliff: {} }, false)
+        .accountsPartial({
+          funder: wallet.publicKey,
+          beneficiary: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: other.lockRecordPda,
+          vault: other.vaultPda,
+          userTokenAccount: userStandardAccount,
+          referrer: wallet.publicKey,
+          referralStats: referralStatsPda,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Should have rejected a mismatched referrer");
+    } catch (e) {
+      assert.include(e.message, "InvalidReferrer");
+    }
+
+    await program.methods
+      .handleUpdateConfig(CONFIG_PARAMS)
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+  });
+
+  it("Emergency Pause: Guardian pauses, only the admin unpauses", async () => {
+    // Strangers cannot pause
+    const stranger = anchor.web3.Keypair.generate();
+    try {
+      await program.methods
+        .handlePause(false)
+        .accountsPartial({ authority: stranger.publicKey })
+        .signers([stranger])
+        .rpc();
+      assert.fail("Only the guardian or admin can pause");
+    } catch (e) {
+      assert.include(e.message, "Unauthorized");
+    }
+
+    // The guardian cannot freeze withdrawals
+    try {
+      await program.methods
+        .handlePause(true)
+        .accountsPartial({ authority: guardian.publicKey })
+        .signers([guardian])
+        .rpc();
+      assert.fail("Only the admin can pause withdrawals");
+    } catch (e) {
+      assert.include(e.message, "Unauthorized");
+    }
+
+    await program.methods
+      .handlePause(false)
+      .accountsPartial({ authority: guardian.publicKey })
+      .signers([guardian])
+      .rpc();
+
+    let config = await program.account.config.fetch(configPda);
+    assert.isTrue(config.paused);
+    assert.isFalse(config.withdrawalsPaused);
+
+    // New locks and burns are stopped
+    const lockId = new anchor.BN(76);
+    const { lockRecordPda, vaultPda } = findPDAs(standardMint, lockId);
+    try {
+      await program.methods
+        .handleLockTokens(
+          LOCK_AMOUNT,
+          new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+          lockId,
+          { cliff: {} },
+          false
+        )
+        .accountsPartial({
+          funder: wallet.publicKey,
+          beneficiary: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: lockRecordPda,
+          vault: vaultPda,
+          userTokenAccount: userStandardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Locking should be paused");
+    } catch (e) {
+      assert.include(e.message, "ProgramPaused");
+    }
+
+    try {
+      await program.methods
+        .handleBurnFromWallet(new anchor.BN(1))
+        .accountsPartial({
+          burner: wallet.publicKey,
+          tokenMint: standardMint,
+          userTokenAccount: userStandardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail("Burning should be paused");
+    } catch (e) {
+      assert.include(e.message, "ProgramPaused");
+    }
+
+    // So are changes to existing locks
+    const existing = findPDAs(standardMint, new anchor.BN(71));
+    try {
+      await program.methods
+        .handleExtendLock(new anchor.BN(71), new anchor.BN(Math.floor(Date.now() / 1000) + 7200))
+        .accountsPartial({
+          owner: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: existing.lockRecordPda,
+        })
+        .rpc();
+      assert.fail("Extending should be paused");
+    } catch (e) {
+      assert.include(e.message, "ProgramPaused");
+    }
+
+    try {
+      await program.methods
+        .handleTransferLockOwnership(new anchor.BN(71), stranger.publicKey)
+        .accountsPartial({
+          owner: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: existing.lockRecordPda,
+        })
+        .rpc();
+      assert.fail("Ownership transfers should be paused");
+    } catch (e) {
+      assert.include(e.message, "ProgramPaused");
+    }
+
+    // The guardian cannot unpause
+    try {
+      await program.methods
+        .handleUnpause()
+        .accountsPartial({ admin: guardian.publicKey })
+        .signers([guardian])
+        .rpc();
+      assert.fail("Only the admin can unpause");
+    } catch (e) {
+      assert.include(e.message, "Unauthorized");
+    }
+
+    // The admin can also pause withdrawals, and unpause clears both flags
+    await program.methods
+      .handlePause(true)
+      .accountsPartial({ authority: wallet.publicKey })
+      .rpc();
+    config = await program.account.config.fetch(configPda);
+    assert.isTrue(config.withdrawalsPaused);
+
+    await program.methods
+      .handleUnpause()
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+    config = await program.account.config.fetch(configPda);
+    assert.isFalse(config.paused);
+    assert.isFalse(config.withdrawalsPaused);
+  });
+
+  it("Mint Policy: Denylist and allowlist-only modes", async () => {
+    const unlockTime = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+    const lock = (
+      mint: anchor.web3.PublicKey,
+      userTokenAccount: anchor.web3.PublicKey,
+      tokenProgram: anchor.web3.PublicKey,
+      lockId: anchor.BN
+    ) => {
+      const { lockRecordPda, vaultPda } = findPDAs(mint, lockId);
+      return program.methods
+        .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
+        .accountsPartial({
+          funder: wallet.publicKey,
+          beneficiary: wallet.publicKey,
+          tokenMint: mint,
+          lockRecord: lockRecordPda,
+          vault: vaultPda,
+          userTokenAccount,
+          tokenProgram,
+        })
+        .rpc();
+    };
+
+    // Denylist mode: a denied mint is refused
+    await program.methods
+      .handleUpdateConfig({ ...CONFIG_PARAMS, mintPolicy: { denylist: {} } })
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+    await program.methods
+      .handleAddMintEntry(token22Mint, false)
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+
+    try {
+      await lock(token22Mint, userToken22Account, TOKEN_2022_PROGRAM_ID, new anchor.BN(77));
+      assert.fail("A denylisted mint should be refused");
+    } catch (e) {
+      assert.include(e.message, "MintNotAllowed");
+    }
+
+    // Allowlist-only mode: mints without an allowing entry are refused
+    await program.methods
+      .handleUpdateConfig({ ...CONFIG_PARAMS, mintPolicy: { allowlistOnly: {} } })
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+
+    try {
+      await lock(standardMint, userStandardAccount, TOKEN_PROGRAM_ID, new anchor.BN(77));
+      assert.fail("A mint without an entry should be refused");
+    } catch (e) {
+      assert.include(e.message, "MintNotAllowed");
+    }
+
+    await program.methods
+      .handleAddMintEntry(standardMint, true)
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+    await lock(standardMint, userStandardAccount, TOKEN_PROGRAM_ID, new anchor.BN(77));
+
+    // Clean up
+    for (const mint of [standardMint, token22Mint]) {
+      await program.methods
+        .handleRemoveMintEntry(mint)
+        .accountsPartial({ admin: wallet.publicKey })
+        .rpc();
+    }
+    await program.methods
+      .handleUpdateConfig(CONFIG_PARAMS)
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+  });
+
+  it("Mint Risk Policy: Flag or reject mints with a permanent delegate", async () => {
+    const RISK_PERMANENT_DELEGATE = 1;
+
+    // A Token-2022 mint whose permanent delegate could drain any vault
+    const mintKeypair = anchor.web3.Keypair.generate();
+    const riskyMint = mintKeypair.publicKey;
+    const mintLen = getMintLen([ExtensionType.PermanentDelegate]);
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.createAccount({
+          fromPubkey: wallet.publicKey,
+          newAccountPubkey: riskyMint,
+          space: mintLen,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializePermanentDelegateInstruction(
+          riskyMint,
+          wallet.publicKey,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(riskyMint, 9, wallet.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [mintKeypair]
+    );
+    const riskyAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        wallet.payer,
+        riskyMint,
+        wallet.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).address;
+    await mintTo(
+      provider.connection,
+      wallet.payer,
+      riskyMint,
+      riskyAccount,
+      wallet.payer,
+      10000,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const lockId = new anchor.BN(1);
+    const { lockRecordPda, vaultPda } = findPDAs(riskyMint, lockId);
+    const lock = () =>
+      program.methods
+        .handleLockTokens(
+          LOCK_AMOUNT,
+          new anchor.BN(Math.floor(Date.now() / 1000) + 3600),
+          lockId,
+          { cliff: {} },
+          false
+        )
+        .accountsPartial({
+          funder: wallet.publicKey,
+          beneficiary: wallet.publicKey,
+          tokenMint: riskyMint,
+          lockRecord: lockRecordPda,
+          vault: vaultPda,
+          userTokenAccount: riskyAccount,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+
+    // Rejected when the policy refuses permanent delegates
+    await program.methods
+      .handleUpdateConfig({ ...CONFIG_PARAMS, rejectedRiskFlags: RISK_PERMANENT_DELEGATE })
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+    try {
+      await lock();
+      assert.fail("A permanent delegate mint should be refused");
+    } catch (e) {
+      assert.include(e.message, "RiskyMint");
+    }
+
+    // Otherwise the lock goes through with the risk recorded
+    await program.methods
+      .handleUpdateConfig(CONFIG_PARAMS)
+      .accountsPartial({ admin: wallet.publicKey })
+      .rpc();
+    await lock();
+
+    const record = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.equal(record.riskFlags, RISK_PERMANENT_DELEGATE);
+  });
+
+  it("Transfer Hook: Every transfer of a hooked mint forwards its extra accounts", async () => {
+    const hookProgram = anchor.workspace.DummyTransferHook as Program<DummyTransferHook>;
+
+    // A Token-2022 mint whose transfers go through the dummy hook
+    const mintKeypair = anchor.web3.Keypair.generate();
+    const hookedMint = mintKeypair.publicKey;
+    const mintLen = getMintLen([ExtensionType.TransferHook]);
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.createAccount({
+          fromPubkey: wallet.publicKey,
+          newAccountPubkey: hookedMint,
+          space: mintLen,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferHookInstruction(
+          hookedMint,
+          wallet.publicKey,
+          hookProgram.programId,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(hookedMint, 9, wallet.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [mintKeypair]
+    );
+
+    const [extraAccountMetaList] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("extra-account-metas"), hookedMint.toBuffer()],
+      hookProgram.programId
+    );
+    const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("counter")],
+      hookProgram.programId
+    );
+    await hookProgram.methods
+      .initializeExtraAccountMetaList()
+      .accountsPartial({
+        payer: wallet.publicKey,
+        extraAccountMetaList,
+        mint: hookedMint,
+        counter: counterPda,
+      })
+      .rpc();
+
+    const hookedAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        wallet.payer,
+        hookedMint,
+        wallet.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      )
+    ).address;
+    await mintTo(
+      provider.connection,
+      wallet.payer,
+      hookedMint,
+      hookedAccount,
+      wallet.payer,
+      10000,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const hookAccounts = [
+      { pubkey: extraAccountMetaList, isSigner: false, isWritable: false },
+      { pubkey: counterPda, isSigner: false, isWritable: true },
+      { pubkey: hookProgram.programId, isSigner: false, isWritable: false },
+    ];
+    const lockId = new anchor.BN(1);
+    const { lockRecordPda, vaultPda } = findPDAs(hookedMint, lockId);
+    const now = Math.floor(Date.now() / 1000);
+    const unlockTime = new anchor.BN(now + 10);
+    const lockAccounts = {
+      funder: wallet.publicKey,
+      beneficiary: wallet.publicKey,
+      tokenMint: hookedMint,
+      lockRecord: lockRecordPda,
+      vault: vaultPda,
+      userTokenAccount: hookedAccount,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+
+    // Without the hook's extra accounts the transfer cannot run
+    try {
+      await program.methods
+        .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
+        .accountsPartial(lockAccounts)
+        .rpc();
+      assert.fail("The transfer hook accounts should be required");
+    } catch (e) {
+      assert.ok(e);
+    }
+
+    await program.methods
+      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
+      .accountsPartial(lockAccounts)
+      .remainingAccounts(hookAccounts)
+      .rpc();
+
+    let counter = await hookProgram.account.counter.fetch(counterPda);
+    assert.equal(counter.transfers.toNumber(), 1, "Hook ran on lock");
+
+    // Top-up, split and merge forward the hook accounts as well
+    await program.methods
+      .handleIncreaseLockAmount(lockId, new anchor.BN(100))
+      .accountsPartial({
+        owner: wallet.publicKey,
+        tokenMint: hookedMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: hookedAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccounts)
+      .rpc();
+
+    const splitId = new anchor.BN(2);
+    const split = findPDAs(hookedMint, splitId);
+    await program.methods
+      .handleSplitLock(lockId, splitId, new anchor.BN(100), unlockTime)
+      .accountsPartial({
+        owner: wallet.publicKey,
+        newOwner: wallet.publicKey,
+        tokenMint: hookedMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        newLockRecord: split.lockRecordPda,
+        newVault: split.vaultPda,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccounts)
+      .rpc();
+
+    await program.methods
+      .handleMergeLocks(lockId, splitId)
+      .accountsPartial({
+        owner: wallet.publicKey,
+        tokenMint: hookedMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        sourceLockRecord: split.lockRecordPda,
+        sourceVault: split.vaultPda,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccounts)
+      .rpc();
+
+    counter = await hookProgram.account.counter.fetch(counterPda);
+    assert.equal(counter.transfers.toNumber(), 4, "Hook ran on top-up, split and merge");
+
+    // The grantor of a revocable grant can still claw it back
+    const employee = anchor.web3.Keypair.generate();
+    const grantId = new anchor.BN(0);
+    const [grantRecordPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("lock_record"),
+        employee.publicKey.toBuffer(),
+        hookedMint.toBuffer(),
+        grantId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [grantVaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), grantRecordPda.toBuffer()],
+      program.programId
+    );
+    const grant = { lockRecordPda: grantRecordPda, vaultPda: grantVaultPda };
+    await program.methods
+      .handleLockTokens(
+        LOCK_AMOUNT,
+        new anchor.BN(now + 1000),
+        null,
+        { linear: { startTimestamp: new anchor.BN(now) } },
+        true
+      )
+      .accountsPartial({
+        ...lockAccounts,
+        beneficiary: employee.publicKey,
+        lockRecord: grant.lockRecordPda,
+        vault: grant.vaultPda,
+      })
+      .remainingAccounts(hookAccounts)
+      .rpc();
+    await program.methods
+      .handleRevokeLock(grantId)
+      .accountsPartial({
+        funder: wallet.publicKey,
+        tokenMint: hookedMint,
+        lockRecord: grant.lockRecordPda,
+        vault: grant.vaultPda,
+        funderTokenAccount: hookedAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccounts)
+      .rpc();
+
+    counter = await hookProgram.account.counter.fetch(counterPda);
+    assert.equal(counter.transfers.toNumber(), 6, "Hook ran on the grant and its revocation");
+    const vestedGrant = (await program.account.lockRecord.fetch(grant.lockRecordPda)).amount.toNumber();
+
+    await new Promise((r) => setTimeout(r, 12000));
+
+    await program.methods
+      .handleWithdrawTokens(lockId, new anchor.BN(1100))
+      .accountsPartial({
+        owner: wallet.publicKey,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: hookedAccount,
+        tokenMint: hookedMint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .remainingAccounts(hookAccounts)
+      .rpc();
+
+    counter = await hookProgram.account.counter.fetch(counterPda);
+    assert.equal(counter.transfers.toNumber(), 7, "Hook ran on withdraw");
+    const balance = await getAccount(provider.connection, hookedAccount, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.equal(Number(balance.amount) + vestedGrant, 10000, "Only the vested part of the grant is still locked");
+  });
+
+  it("Net Withdrawal: Receive an exact amount from a transfer-fee lock", async () => {
+    const lockId = new anchor.BN(4);
+    const { lockRecordPda, vaultPda } = findPDAs(feeMint, lockId);
+    const unlockTime = new anchor.BN(Math.floor(Date.now() / 1000) + 5);
+
+    await program.methods
+      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
+      .accountsPartial({
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
+        tokenMint: feeMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userFeeAccount,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    await new Promise((r) => setTimeout(r, 6000));
+
+    const before = await getAccount(provider.connection, userFeeAccount, undefined, TOKEN_2022_PROGRAM_ID);
+
+    // With a 10% fee, receiving 450 takes 500 out of the vault
+    await program.methods
+      .handleWithdrawTokensNet(lockId, new anchor.BN(450))
+      .accountsPartial({
+        owner: wallet.publicKey,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userFeeAccount,
+        tokenMint: feeMint,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+    const after = await getAccount(provider.connection, userFeeAccount, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.equal(Number(after.amount - before.amount), 450, "Owner receives exactly the net amount");
+
+    const record = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.equal(record.amount.toNumber(), 400, "The gross amount is deducted from the lock");
+  });
+
+  it("Sync Lock: Credit or sweep tokens sent straight to the vault", async () => {
+    const lockId = new anchor.BN(78);
+    const { lockRecordPda, vaultPda } = findPDAs(standardMint, lockId);
+    const unlockTime = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+
+    await program.methods
+      .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
+      .accountsPartial({
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: userStandardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    const sync = (mode: object) =>
+      program.methods
+        .handleSyncLock(lockId, mode as any)
+        .accountsPartial({
+          owner: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: lockRecordPda,
+          vault: vaultPda,
+          ownerTokenAccount: userStandardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    // Nothing to do while the vault matches the record
+    try {
+      await sync({ credit: {} });
+      assert.fail("Should have found no surplus");
+    } catch (e) {
+      assert.include(e.message, "NothingToSync");
+    }
+
+    // Someone sends 100 straight to the vault: credit it to the lock
+    await transferChecked(
+      provider.connection,
+      wallet.payer,
+      userStandardAccount,
+      standardMint,
+      vaultPda,
+      wallet.payer,
+      100,
+      9,
+      [],
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    await sync({ credit: {} });
+    let record = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.equal(record.amount.toNumber(), 1100);
+
+    // Another 50 arrive: sweep them back out while the lock stays locked
+    await transferChecked(
+      provider.connection,
+      wallet.payer,
+      userStandardAccount,
+      standardMint,
+      vaultPda,
+      wallet.payer,
+      50,
+      9,
+      [],
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    const before = await getAccount(provider.connection, userStandardAccount, undefined, TOKEN_PROGRAM_ID);
+    await sync({ sweep: {} });
+    const after = await getAccount(provider.connection, userStandardAccount, undefined, TOKEN_PROGRAM_ID);
+    assert.equal(Number(after.amount - before.amount), 50);
+
+    record = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.equal(record.amount.toNumber(), 1100, "Sweeping leaves the lock untouched");
+    const vault = await getAccount(provider.connection, vaultPda, undefined, TOKEN_PROGRAM_ID);
+    assert.equal(Number(vault.amount), 1100);
+
+    // A vesting lock can only sweep: credited tokens would count as partly vested already
+    const vestingLockId = new anchor.BN(79);
+    const vesting = findPDAs(standardMint, vestingLockId);
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .handleLockTokens(
+        LOCK_AMOUNT,
+        new anchor.BN(now + 3600),
+        vestingLockId,
+        { linear: { startTimestamp: new anchor.BN(now) } },
+        false
+      )
+      .accountsPartial({
+        funder: wallet.publicKey,
+        beneficiary: wallet.publicKey,
+        tokenMint: standardMint,
+        lockRecord: vesting.lockRecordPda,
+        vault: vesting.vaultPda,
+        userTokenAccount: userStandardAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+    await transferChecked(
+      provider.connection,
+      wallet.payer,
+      userStandardAccount,
+      standardMint,
+      vesting.vaultPda,
+      wallet.payer,
+      20,
+      9,
+      [],
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    const syncVesting = (mode: object) =>
+      program.methods
+        .handleSyncLock(vestingLockId, mode as any)
+        .accountsPartial({
+          owner: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: vesting.lockRecordPda,
+          vault: vesting.vaultPda,
+          ownerTokenAccount: userStandardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    try {
+      await syncVesting({ credit: {} });
+      assert.fail("Crediting a vesting lock should fail");
+    } catch (e) {
+      assert.include(e.message, "NotCliffLock");
+    }
+    await syncVesting({ sweep: {} });
+    record = await program.account.lockRecord.fetch(vesting.lockRecordPda);
+    assert.equal(record.amount.toNumber(), 1000);
+  });
+
+  it("Lock Counter: Ids are assigned automatically when none is given", async () => {
+    const beneficiary = anchor.web3.Keypair.generate();
+    const unlockTime = new anchor.BN(Math.floor(Date.now() / 1000) + 3600);
+    const lockCounterFor = (owner: anchor.web3.PublicKey) =>
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("lock_counter"), owner.toBuffer()],
+        program.programId
+      )[0];
+    const pdasFor = (owner: anchor.web3.PublicKey, lockId: number) => {
+      const [lockRecordPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("lock_record"),
+          owner.toBuffer(),
+          standardMint.toBuffer(),
+          new anchor.BN(lockId).toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+      const [vaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("vault"), lockRecordPda.toBuffer()],
+        program.programId
+      );
+      return { lockRecordPda, vaultPda };
+    };
+    // Locks for `owner` and returns the id the program reports through return data
+    const lock = async (owner: anchor.web3.PublicKey, lockId: anchor.BN | null, expectedId: number) => {
+      const { lockRecordPda, vaultPda } = pdasFor(owner, expectedId);
+      const signature = await program.methods
+        .handleLockTokens(LOCK_AMOUNT, unlockTime, lockId, { cliff: {} }, false)
+        .accountsPartial({
+          funder: wallet.publicKey,
+          beneficiary: owner,
+          lockCounter: lockCounterFor(owner),
+          tokenMint: standardMint,
+          lockRecord: lockRecordPda,
+          vault: vaultPda,
+          userTokenAccount: userStandardAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc({ commitment: "confirmed" });
+      const tx = await provider.connection.getTransaction(signature, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      const [returnData] = tx.meta.returnData.data;
+      return Number(Buffer.from(returnData, "base64").readBigUInt64LE(0));
+    };
+
+    // The first automatic id is 0, and the program returns it
+    assert.equal(await lock(beneficiary.publicKey, null, 0), 0);
+    let record = await program.account.lockRecord.fetch(pdasFor(beneficiary.publicKey, 0).lockRecordPda);
+    assert.equal(record.id.toNumber(), 0);
+
+    // Someone funding the lock cannot pick the id, so it cannot exhaust or squat the counter
+    try {
+      await lock(beneficiary.publicKey, new anchor.BN("18446744073709551614"), 0);
+      assert.fail("A third party should not be able to choose the lock id");
+    } catch (e) {
+      assert.include(e.toString(), "LockIdNotAllowed");
+    }
+    assert.equal(await lock(beneficiary.publicKey, null, 1), 1);
+    let counter = await program.account.lockCounter.fetch(lockCounterFor(beneficiary.publicKey));
+    assert.equal(counter.nextId.toNumber(), 2);
+
+    // A self-funded explicit id still works and pushes the owner's counter past it
+    assert.equal(await lock(wallet.publicKey, new anchor.BN(1000), 1000), 1000);
+    counter = await program.account.lockCounter.fetch(lockCounterFor(wallet.publicKey));
+    assert.equal(counter.nextId.toNumber(), 1001);
+    assert.equal(await lock(wallet.publicKey, null, 1001), 1001);
+    record = await program.account.lockRecord.fetch(pdasFor(wallet.publicKey, 1001).lockRecordPda);
+    assert.equal(record.id.toNumber(), 1001);
+  });
+
+  it("Mint Stats: Per-mint totals follow locks, burns and withdrawals", async () => {
+    // A fresh mint so the stats only reflect this test
+    const statsMint = await createMint(
+      provider.connection,
+      wallet.payer,
+      wallet.publicKey,
+      null,
+      9,
+      undefined,
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    const userStatsAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        wallet.payer,
+        statsMint,
+        wallet.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_PROGRAM_ID
+      )
+    ).address;
+    await mintTo(
+      provider.connection,
+      wallet.payer,
+      statsMint,
+      userStatsAccount,
+      wallet.payer,
+      10000,
+      [],
+      undefined,
+      TOKEN_PROGRAM_ID
+    );
+    const [mintStatsPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_stats"), statsMint.toBuffer()],
+      program.programId
+    );
+
+    const now = Math.floor(Date.now() / 1000);
+    const shortUnlock = new anchor.BN(now + 10);
+    const longUnlock = new anchor.BN(now + 3600);
+    const lock = (lockId: anchor.BN, amount: number, unlockTime: anchor.BN) => {
+      const { lockRecordPda, vaultPda } = findPDAs(statsMint, lockId);
+      return program.methods
+        .handleLockTokens(new anchor.BN(amount), unlockTime, lockId, { cliff: {} }, false)
+        .accountsPartial({
+          funder: wallet.publicKey,
+          beneficiary: wallet.publicKey,
+          tokenMint: statsMint,
+          lockRecord: lockRecordPda,
+          vault: vaultPda,
+          userTokenAccount: userStatsAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    };
+
+    await lock(new anchor.BN(0), 1000, shortUnlock);
+    await lock(new anchor.BN(1), 2000, longUnlock);
+
+    let stats = await program.account.mintStats.fetch(mintStatsPda);
+    assert.ok(stats.mint.equals(statsMint));
+    assert.equal(stats.totalLocked.toNumber(), 3000);
+    assert.equal(stats.activeLocks.toNumber(), 2);
+    assert.equal(stats.allTimeLocked.toNumber(), 3000);
+    assert.equal(stats.allTimeLatestUnlock.toNumber(), longUnlock.toNumber());
+
+    // Burning from a lock lowers the current total but not the all-time one
+    const longLock = findPDAs(statsMint, new anchor.BN(1));
+    await program.methods
+      .handleBurnFromLock(new anchor.BN(500), new anchor.BN(1))
+      .accountsPartial({
+        owner: wallet.publicKey,
+        tokenMint: statsMint,
+        lockRecord: longLock.lockRecordPda,
+        vault: longLock.vaultPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    stats = await program.account.mintStats.fetch(mintStatsPda);
+    assert.equal(stats.totalLocked.toNumber(), 2500);
+    assert.equal(stats.allTimeLocked.toNumber(), 3000);
+
+    // A full withdrawal closes the lock and drops it from the active count
+    console.log("Mint Stats: Waiting 12s for the short lock to expire...");
+    await new Promise((r) => setTimeout(r, 12000));
+
+    const shortLock = findPDAs(statsMint, new anchor.BN(0));
+    await program.methods
+      .handleWithdrawTokens(new anchor.BN(0), new anchor.BN(1000))
+      .accountsPartial({
+        owner: wallet.publicKey,
+        lockRecord: shortLock.lockRecordPda,
+        vault: shortLock.vaultPda,
+        userTokenAccount: userStatsAccount,
+        tokenMint: statsMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc();
+
+    stats = await program.account.mintStats.fetch(mintStatsPda);
+    assert.equal(stats.totalLocked.toNumber(), 1500);
+    assert.equal(stats.activeLocks.toNumber(), 1);
+    assert.equal(stats.allTimeLocked.toNumber(), 3000);
+    assert.equal(stats.allTimeLatestUnlock.toNumber(), longUnlock.toNumber(), "No open lock unlocks later");
+  });
+
+  it("Migrate Lock: A legacy record moves to the current layout and stays usable", async () => {
+    // Preloaded from tests/fixtures: a pre-schedule lock #7 of 1000 tokens, unlocked since 1970
+    const legacyOwner = anchor.web3.Keypair.fromSeed(Uint8Array.from(Array(32).fill(7)));
+    const legacyMint = new anchor.web3.PublicKey("2KW2XRd9kwqet15Aha2oK3tYvd3nWbTFH1MBiRAv1BE1");
+    const lockId = new anchor.BN(7);
+    const [lockRecordPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("lock_record"),
+        legacyOwner.publicKey.toBuffer(),
+        legacyMint.toBuffer(),
+        lockId.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const [vaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), lockRecordPda.toBuffer()],
+      program.programId
+    );
+    const [mintStatsPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("mint_stats"), legacyMint.toBuffer()],
+      program.programId
+    );
+    const [lockCounterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("lock_counter"), legacyOwner.publicKey.toBuffer()],
+      program.programId
+    );
+
+    // Anyone can pay to migrate it
+    await program.methods
+      .handleMigrateLock(lockId)
+      .accountsPartial({
+        payer: wallet.publicKey,
+        owner: legacyOwner.publicKey,
+        tokenMint: legacyMint,
+        lockRecord: lockRecordPda,
+      })
+      .rpc();
+
+    const record = await program.account.lockRecord.fetch(lockRecordPda);
+    assert.ok(record.owner.equals(legacyOwner.publicKey));
+    assert.ok(record.originalOwner.equals(legacyOwner.publicKey), "The seed wallet becomes the original owner");
+    assert.ok(record.vault.equals(vaultPda));
+    assert.equal(record.amount.toNumber(), 1000);
+    assert.deepEqual(record.schedule, { cliff: {} });
+
+    let stats = await program.account.mintStats.fetch(mintStatsPda);
+    assert.equal(stats.activeLocks.toNumber(), 1, "The migrated lock is counted");
+    assert.equal(stats.totalLocked.toNumber(), 1000);
+    const counter = await program.account.lockCounter.fetch(lockCounterPda);
+    assert.equal(counter.nextId.toNumber(), 8, "Assigned ids skip past the legacy id");
+
+    // The owner withdraws through the current seeds, which closes the lock
+    const ownerTokenAccount = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        wallet.payer,
+        legacyMint,
+        legacyOwner.publicKey,
+        false,
+        undefined,
+        undefined,
+        TOKEN_PROGRAM_ID
+      )
+    ).address;
+    await program.methods
+      .handleWithdrawTokens(lockId, new anchor.BN(1000))
+      .accountsPartial({
+        owner: legacyOwner.publicKey,
+        lockRecord: lockRecordPda,
+        vault: vaultPda,
+        userTokenAccount: ownerTokenAccount,
+        tokenMint: legacyMint,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([legacyOwner])
+      .rpc();
+
+    const balance = await getAccount(provider.connection, ownerTokenAccount, undefined, TOKEN_PROGRAM_ID);
+    assert.equal(Number(balance.amount), 1000);
+    assert.isNull(await program.account.lockRecord.fetchNullable(lockRecordPda), "The lock is closed");
+    stats = await program.account.mintStats.fetch(mintStatsPda);
+    assert.equal(stats.activeLocks.toNumber(), 0);
+    assert.equal(stats.totalLocked.toNumber(), 0);
+  });
+
+  it("Migrate Lock: Records already in the current layout are rejected", async () => {
+    const lockId = new anchor.BN(10);
+    const { lockRecordPda } = findPDAs(standardMint, lockId);
+    try {
+      await program.methods
+        .handleMigrateLock(lockId)
+        .accountsPartial({
+          payer: wallet.publicKey,
+          owner: wallet.publicKey,
+          tokenMint: standardMint,
+          lockRecord: lockRecordPda,
+        })
+        .rpc();
+      assert.fail("A current lock record should not be migrated again");
+    } catch (e) {
+      assert.include(e.toString(), "LockAlreadyMigrated");
+    }
+  });
 });